@@ -1,5 +1,8 @@
+use pryx_host::sidecar::manager::SidecarManager;
 use pryx_host::sidecar::{find_pryx_core_binary, SidecarConfig, SidecarProcess, SidecarStatus};
 use pryx_host::sidecar::permissions::{PermissionManager, PermissionDialogConfig, ApprovalRequest, ApprovalResponse};
+use pryx_host::pty::{kill_pty, resize_pty, spawn_pty, write_pty, PtyManager};
+use pryx_host::settings::SettingsStore;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager, State};
 use tauri_plugin_updater::UpdaterExt;
@@ -14,84 +17,52 @@ fn get_sidecar_status(state: State<Arc<SidecarProcess>>) -> SidecarStatus {
 #[tauri::command]
 async fn request_tool_approval(
     app: AppHandle,
+    settings: State<'_, Arc<SettingsStore>>,
+    permission_manager: State<'_, Arc<PermissionManager>>,
     request: ApprovalRequest,
-) -> Result<bool, String> {
-    let config_path = app.path().join("permissions.json");
-    let config = match std::fs::read_to_string(&config_path) {
-        Ok(content) => {
-            match serde_json::from_str::<PermissionDialogConfig>(&content) {
-                Ok(cfg) => cfg,
-                Err(_) => PermissionDialogConfig::default(),
-            }
-        }
-        Err(_) => {
-            eprintln!("Failed to read permissions config, using defaults");
-            PermissionDialogConfig::default()
-        }
+) -> Result<ApprovalResponse, String> {
+    let config = settings.get().permissions.clone();
+    let outcome = permission_manager
+        .request_approval(&app, request.clone(), &config)
+        .await;
+
+    let audit_path = app
+        .path()
+        .app_config_dir()
+        .unwrap_or_default()
+        .join("approvals_audit.jsonl");
+    let entry = pryx_host::sidecar::permissions::ApprovalAuditEntry {
+        request_id: request.request_id.clone(),
+        tool_name: request.tool_name.clone(),
+        args_digest: pryx_host::sidecar::permissions::digest_args(&request.args),
+        outcome: outcome.clone(),
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis(),
     };
-
-    let permission_manager = Arc::new(PermissionManager::new(config));
-    let manager_clone = permission_manager.clone();
-    let request_clone = request.clone();
-
-    let result = tokio::task::spawn_blocking(move || {
-        match manager_clone.request_approval(&app, request_clone) {
-            Ok(ApprovalResponse::Approved) => true,
-            Ok(ApprovalResponse::Denied) => false,
-            Ok(ApprovalResponse::Cancelled) => false,
-            Err(e) => {
-                eprintln!("Approval error: {}", e);
-                false
-            }
-        }
-    }).await;
-
-    match result {
-        Ok(approved) => Ok(approved),
-        Err(e) => Err(e),
+    if let Err(e) = pryx_host::sidecar::permissions::record_audit_entry(&audit_path, &entry) {
+        eprintln!("Failed to record approval audit entry: {}", e);
     }
+
+    Ok(outcome)
 }
 
 // Command to list pending approvals
 #[tauri::command]
-async fn list_pending_approvals(app: AppHandle) -> Vec<ApprovalRequest> {
-    let config_path = app.path().join("permissions.json");
-    let config = match std::fs::read_to_string(&config_path) {
-        Ok(content) => {
-            match serde_json::from_str::<PermissionDialogConfig>(&content) {
-                Ok(cfg) => cfg,
-                Err(_) => PermissionDialogConfig::default(),
-            }
-        }
-        Err(_) => {
-            eprintln!("Failed to read permissions config, using defaults");
-            PermissionDialogConfig::default()
-        }
-    };
-
-    let permission_manager = Arc::new(PermissionManager::new(config));
-    permission_manager.list_pending()
+async fn list_pending_approvals(
+    permission_manager: State<'_, Arc<PermissionManager>>,
+) -> Result<Vec<ApprovalRequest>, String> {
+    Ok(permission_manager.list_pending())
 }
 
 // Command to cancel an approval request
 #[tauri::command]
-async fn cancel_approval(app: AppHandle, request_id: String) -> Result<(), String> {
-    let config_path = app.path().join("permissions.json");
-    let config = match std::fs::read_to_string(&config_path) {
-        Ok(content) => {
-            match serde_json::from_str::<PermissionDialogConfig>(&content) {
-                Ok(cfg) => cfg,
-                Err(_) => PermissionDialogConfig::default(),
-            }
-        }
-        Err(_) => {
-            eprintln!("Failed to read permissions config, using defaults");
-            PermissionDialogConfig::default()
-        }
-    };
-
-    let permission_manager = Arc::new(PermissionManager::new(config));
-    permission_manager.cancel_request(request_id);
+async fn cancel_approval(
+    permission_manager: State<'_, Arc<PermissionManager>>,
+    request_id: String,
+) -> Result<(), String> {
+    permission_manager.cancel_request(request_id.clone());
     eprintln!("Cancelled approval request: {}", request_id);
     Ok(())
 }
@@ -99,12 +70,12 @@ async fn cancel_approval(app: AppHandle, request_id: String) -> Result<(), Strin
 // Command to configure permissions
 #[tauri::command]
 async fn configure_permissions(
-    app: AppHandle,
+    settings: State<'_, Arc<SettingsStore>>,
     config: PermissionDialogConfig,
 ) -> Result<(), String> {
-    let config_path = app.path().join("permissions.json");
-    let config_json = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
-    std::fs::write(&config_path, config_json).map_err(|e| e.to_string())?;
+    let mut updated = (*settings.get()).clone();
+    updated.permissions = config;
+    settings.save(updated).map_err(|e| e.to_string())?;
     eprintln!("Permissions config updated");
     Ok(())
 }
@@ -205,7 +176,20 @@ async fn main() {
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(Arc::new(SidecarProcess::new(SidecarConfig::default())))
+        .manage(Arc::new(PtyManager::new()))
+        .manage(Arc::new(PermissionManager::new()))
         .setup(|app| {
+            let settings_path = app
+                .path()
+                .app_config_dir()
+                .unwrap_or_default()
+                .join("settings.json");
+            let settings = Arc::new(SettingsStore::load(settings_path));
+            settings.watch();
+            app.manage(settings);
+
+            app.manage(Arc::new(SidecarManager::new(app.handle().clone())));
+
             let handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 if let Ok(updater) = handle.updater() {
@@ -217,6 +201,25 @@ async fn main() {
                     }
                 }
             });
+
+            // Forward every PTY session's output to the webview as it's
+            // read, so `spawn_pty`/`write_pty` aren't one-way: the frontend
+            // listens for `pty-output` and demuxes by `PtyOutput::session_id`.
+            let pty_manager = app.state::<Arc<PtyManager>>().inner().clone();
+            let pty_handle = app.handle().clone();
+            let mut pty_output = pty_manager.subscribe_output();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match pty_output.recv().await {
+                        Ok(output) => {
+                            let _ = pty_handle.emit("pty-output", output);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -232,6 +235,10 @@ async fn main() {
             dispatch_notification,
             read_clipboard,
             write_clipboard,
+            spawn_pty,
+            write_pty,
+            resize_pty,
+            kill_pty,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
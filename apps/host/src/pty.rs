@@ -0,0 +1,235 @@
+//! Interactive, PTY-backed child process sessions.
+//!
+//! Distinct from the single managed `pryx-core` sidecar: this module lets the
+//! frontend spawn arbitrary interactive commands (shells, REPLs, long-running
+//! CLI tools), each backed by its own pseudo-terminal, and stream their
+//! output back as `pty-output` Tauri events (wired up in `main.rs::main()`'s
+//! `setup`) while accepting keystroke input back via `write_pty`.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// A chunk of output read from a PTY session, fanned out to subscribers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PtyOutput {
+    pub session_id: String,
+    /// Raw bytes from the PTY, base64-encoded for transport over text frames.
+    pub data: String,
+}
+
+struct PtySession {
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn std::io::Write + Send>,
+    child: Box<dyn portable_pty::Child + Send + Sync>,
+}
+
+/// Owns all live PTY sessions spawned by the frontend.
+#[derive(Clone)]
+pub struct PtyManager {
+    sessions: Arc<Mutex<HashMap<String, PtySession>>>,
+    output: broadcast::Sender<PtyOutput>,
+}
+
+impl Default for PtyManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PtyManager {
+    pub fn new() -> Self {
+        Self {
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            output: broadcast::channel(1024).0,
+        }
+    }
+
+    /// Subscribe to output from every PTY session; consumers filter by
+    /// `session_id` on the frontend (or in whatever task forwards this to
+    /// it, e.g. `main.rs`'s `pty-output` event emitter).
+    pub fn subscribe_output(&self) -> broadcast::Receiver<PtyOutput> {
+        self.output.subscribe()
+    }
+
+    pub fn spawn(
+        &self,
+        cmd: String,
+        args: Vec<String>,
+        cwd: Option<PathBuf>,
+    ) -> Result<String, PtyError> {
+        let pty_system = native_pty_system();
+        let pair = pty_system
+            .openpty(PtySize {
+                rows: 24,
+                cols: 80,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| PtyError::Spawn(e.to_string()))?;
+
+        let mut builder = CommandBuilder::new(cmd);
+        builder.args(args);
+        if let Some(cwd) = cwd {
+            builder.cwd(cwd);
+        }
+
+        let child = pair
+            .slave
+            .spawn_command(builder)
+            .map_err(|e| PtyError::Spawn(e.to_string()))?;
+        drop(pair.slave);
+
+        let writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| PtyError::Spawn(e.to_string()))?;
+        let mut reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| PtyError::Spawn(e.to_string()))?;
+
+        let session_id = Uuid::new_v4().to_string();
+
+        let output_tx = self.output.clone();
+        let reader_session_id = session_id.clone();
+        std::thread::spawn(move || {
+            use base64::Engine;
+            let mut buf = [0u8; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        let data = base64::engine::general_purpose::STANDARD.encode(&buf[..n]);
+                        let _ = output_tx.send(PtyOutput {
+                            session_id: reader_session_id.clone(),
+                            data,
+                        });
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+
+        let session = PtySession {
+            master: pair.master,
+            writer,
+            child,
+        };
+
+        self.sessions
+            .lock()
+            .expect("mutex poisoned")
+            .insert(session_id.clone(), session);
+
+        Ok(session_id)
+    }
+
+    pub fn write(&self, session_id: &str, data: &[u8]) -> Result<(), PtyError> {
+        let mut sessions = self.sessions.lock().expect("mutex poisoned");
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| PtyError::NotFound(session_id.to_string()))?;
+        session
+            .writer
+            .write_all(data)
+            .map_err(|e| PtyError::Io(e.to_string()))
+    }
+
+    pub fn resize(&self, session_id: &str, rows: u16, cols: u16) -> Result<(), PtyError> {
+        let sessions = self.sessions.lock().expect("mutex poisoned");
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| PtyError::NotFound(session_id.to_string()))?;
+        session
+            .master
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| PtyError::Io(e.to_string()))
+    }
+
+    pub fn kill(&self, session_id: &str) -> Result<(), PtyError> {
+        let mut sessions = self.sessions.lock().expect("mutex poisoned");
+        let mut session = sessions
+            .remove(session_id)
+            .ok_or_else(|| PtyError::NotFound(session_id.to_string()))?;
+        session
+            .child
+            .kill()
+            .map_err(|e| PtyError::Io(e.to_string()))
+    }
+
+    /// Drop sessions whose child process has already exited.
+    pub fn reap_finished(&self) {
+        let mut sessions = self.sessions.lock().expect("mutex poisoned");
+        sessions.retain(|_, session| !matches!(session.child.try_wait(), Ok(Some(_))));
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PtyError {
+    #[error("Failed to spawn PTY process: {0}")]
+    Spawn(String),
+
+    #[error("No PTY session with id '{0}'")]
+    NotFound(String),
+
+    #[error("PTY I/O error: {0}")]
+    Io(String),
+}
+
+/// Tauri command: spawn an interactive, PTY-backed process and return its
+/// session id.
+#[tauri::command]
+pub async fn spawn_pty(
+    manager: tauri::State<'_, Arc<PtyManager>>,
+    cmd: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+) -> Result<String, String> {
+    manager
+        .spawn(cmd, args, cwd.map(PathBuf::from))
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command: feed raw bytes to a PTY session's stdin.
+#[tauri::command]
+pub async fn write_pty(
+    manager: tauri::State<'_, Arc<PtyManager>>,
+    session_id: String,
+    data: Vec<u8>,
+) -> Result<(), String> {
+    manager.write(&session_id, &data).map_err(|e| e.to_string())
+}
+
+/// Tauri command: resize a PTY session's window.
+#[tauri::command]
+pub async fn resize_pty(
+    manager: tauri::State<'_, Arc<PtyManager>>,
+    session_id: String,
+    rows: u16,
+    cols: u16,
+) -> Result<(), String> {
+    manager
+        .resize(&session_id, rows, cols)
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command: terminate a PTY session and remove it.
+#[tauri::command]
+pub async fn kill_pty(
+    manager: tauri::State<'_, Arc<PtyManager>>,
+    session_id: String,
+) -> Result<(), String> {
+    manager.kill(&session_id).map_err(|e| e.to_string())
+}
@@ -1,45 +1,145 @@
 use crate::server::ServerConfig;
 use axum::{
     body::Body,
-    http::{Request, Response, StatusCode},
+    extract::State,
+    http::{HeaderMap, Request, Response, StatusCode},
     middleware::Next,
     response::IntoResponse,
 };
+use axum_extra::extract::cookie::CookieJar;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
 
-pub async fn auth_middleware(
-    request: Request<Body>,
-    next: Next,
-) -> Result<Response<Body>, Response<Body>> {
-    let config = request
-        .extensions()
-        .get::<ServerConfig>()
-        .ok_or_else(|| (StatusCode::INTERNAL_SERVER_ERROR, "Config missing").into_response())?;
-
-    // Get sidecar to access the token
-    let sidecar = config.sidecar.as_ref().ok_or_else(|| {
-        (StatusCode::SERVICE_UNAVAILABLE, "Sidecar not initialized").into_response()
-    })?;
-
-    let expected_token = sidecar.admin_token.lock().expect("mutex poisoned").clone();
-
-    // 1. Check Authorization header
-    if let Some(auth_header) = request.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if auth_str == format!("Bearer {}", expected_token) {
-                return Ok(next.run(request).await);
-            }
+/// Identity established by a successful [`ApiAuth::authenticate`] call,
+/// attached to the request as an extension so downstream handlers can read
+/// who's calling without re-running auth themselves. The static-token
+/// backend only ever reports one principal; a pluggable backend (proxy-header
+/// trust, an external verifier) can report a real one.
+#[derive(Debug, Clone)]
+pub struct AuthContext {
+    pub principal: String,
+}
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("unauthorized")]
+    Unauthorized,
+    #[error("authentication backend unavailable: {0}")]
+    Unavailable(String),
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response<Body> {
+        match self {
+            AuthError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized").into_response(),
+            AuthError::Unavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg).into_response(),
+        }
+    }
+}
+
+/// Credential verification policy for the API routes, decoupled from
+/// `auth_middleware`'s router wiring so operators can swap it (a different
+/// token source, a reverse-proxy header, an external identity service)
+/// without touching `app_router`.
+#[async_trait::async_trait]
+pub trait ApiAuth: Send + Sync {
+    async fn authenticate(
+        &self,
+        headers: &HeaderMap,
+        cookies: &CookieJar,
+    ) -> Result<AuthContext, AuthError>;
+
+    /// Value to set as the `pryx_admin_token` bootstrap cookie when serving
+    /// the root page, if this backend has one shared secret to hand the UI.
+    /// Backends with no such notion (proxy-header trust, an external
+    /// verifier) keep the default of `None` and skip the cookie.
+    fn bootstrap_token(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Default backend: a single admin bearer token, checked against the
+/// `Authorization` header or the `pryx_admin_token` cookie the web UI sets on
+/// login. This is the behavior `auth_middleware` always had before it was
+/// pulled out behind [`ApiAuth`].
+pub struct StaticTokenAuth {
+    token: Arc<Mutex<String>>,
+}
+
+const TOKEN_CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+impl StaticTokenAuth {
+    pub fn new(token: String) -> Self {
+        Self {
+            token: Arc::new(Mutex::new(token)),
         }
     }
 
-    // 2. Check Cookie (for browser UI)
-    if let Some(cookie_header) = request.headers().get("Cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            if cookie_str.contains(&format!("pryx_admin_token={}", expected_token)) {
-                return Ok(next.run(request).await);
+    /// Generate a fresh random admin token, used to seed `ServerConfig`'s
+    /// default `ApiAuth` so a host started without explicit auth config
+    /// still gets a unique per-run credential rather than a blank one.
+    pub fn generate() -> Self {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        let token = (0..32)
+            .map(|_| TOKEN_CHARSET[rng.gen_range(0..TOKEN_CHARSET.len())] as char)
+            .collect();
+        Self::new(token)
+    }
+
+    /// The token clients must present; also used by `root_handler` to set the
+    /// `pryx_admin_token` cookie after a successful page load.
+    pub fn token(&self) -> String {
+        self.token.lock().expect("mutex poisoned").clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiAuth for StaticTokenAuth {
+    async fn authenticate(
+        &self,
+        headers: &HeaderMap,
+        cookies: &CookieJar,
+    ) -> Result<AuthContext, AuthError> {
+        let expected = self.token();
+
+        if let Some(auth_header) = headers.get("Authorization") {
+            if let Ok(auth_str) = auth_header.to_str() {
+                if auth_str == format!("Bearer {}", expected) {
+                    return Ok(AuthContext {
+                        principal: "admin".to_string(),
+                    });
+                }
             }
         }
+
+        if cookies.get("pryx_admin_token").is_some_and(|c| c.value() == expected) {
+            return Ok(AuthContext {
+                principal: "admin".to_string(),
+            });
+        }
+
+        Err(AuthError::Unauthorized)
+    }
+
+    fn bootstrap_token(&self) -> Option<String> {
+        Some(self.token())
     }
+}
+
+pub async fn auth_middleware(
+    State(config): State<ServerConfig>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Result<Response<Body>, Response<Body>> {
+    let cookies = CookieJar::from_headers(request.headers());
+    let auth_context = config
+        .auth
+        .authenticate(request.headers(), &cookies)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+    request.extensions_mut().insert(auth_context);
 
-    // Unauthorized
-    Err((StatusCode::UNAUTHORIZED, "Unauthorized").into_response())
+    Ok(next.run(request).await)
 }
@@ -1,11 +1,21 @@
+use crate::server::query::ListQuery;
 use crate::server::ServerConfig;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    response::{IntoResponse, Response},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     Json,
 };
+use futures::Stream;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::time::Duration;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt as _;
 
 // Helper to call RPC and handle response
 async fn rpc_call(config: ServerConfig, method: &str, params: Value) -> Response {
@@ -22,6 +32,25 @@ async fn rpc_call(config: ServerConfig, method: &str, params: Value) -> Response
     }
 }
 
+/// Like `rpc_call`, but for list endpoints: the RPC is expected to return a
+/// bare JSON array, which `query` then filters/sorts/paginates into the
+/// common envelope. A non-array response is passed through unfiltered, since
+/// not every core might model a given list RPC that way.
+async fn list_rpc_call(config: ServerConfig, method: &str, query: ListQuery) -> Response {
+    if let Some(sidecar) = config.sidecar {
+        match sidecar.call_rpc(method, Value::Null).await {
+            Ok(Value::Array(items)) => Json(query.apply(items)).into_response(),
+            Ok(res) => Json(res).into_response(),
+            Err(e) => {
+                log::error!("RPC Error ({}): {}", method, e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }
+        }
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "Sidecar not initialized").into_response()
+    }
+}
+
 pub async fn health_handler(State(config): State<ServerConfig>) -> Response {
     rpc_call(config, "admin.health", Value::Null).await
 }
@@ -42,10 +71,58 @@ pub async fn models_handler(State(config): State<ServerConfig>) -> Response {
     rpc_call(config, "admin.models.list", Value::Null).await
 }
 
+/// Unlike the other admin handlers, this doesn't proxy an RPC to the core —
+/// it inspects the host OS's socket table directly, since it's reporting on
+/// who is connected to the core's port, not something the core itself knows.
+pub async fn clients_handler(State(config): State<ServerConfig>) -> Response {
+    match config.sidecar {
+        Some(sidecar) => Json(sidecar.connected_clients()).into_response(),
+        None => (StatusCode::SERVICE_UNAVAILABLE, "Sidecar not initialized").into_response(),
+    }
+}
+
+// --- Multi-instance handlers ---
+//
+// These resolve the target `SidecarProcess` from `config.sidecars` by
+// instance id rather than always hitting the single default `config.sidecar`,
+// so one host can expose several isolated cores side by side.
+
+/// RPC call scoped to a named instance, mirroring `rpc_call` above.
+async fn instance_rpc_call(config: ServerConfig, iid: &str, method: &str, params: Value) -> Response {
+    let Some(manager) = config.sidecars else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "No sidecar manager configured").into_response();
+    };
+    let Some(sidecar) = manager.get(iid) else {
+        return (StatusCode::NOT_FOUND, format!("No instance '{}'", iid)).into_response();
+    };
+    match sidecar.call_rpc(method, params).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => {
+            log::error!("RPC Error ({} on instance {}): {}", method, iid, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+/// Status of every registered instance, keyed by instance id.
+pub async fn instances_list_handler(State(config): State<ServerConfig>) -> Response {
+    match config.sidecars {
+        Some(manager) => Json(manager.status_all()).into_response(),
+        None => (StatusCode::SERVICE_UNAVAILABLE, "No sidecar manager configured").into_response(),
+    }
+}
+
+pub async fn instance_channels_list_handler(
+    State(config): State<ServerConfig>,
+    Path(iid): Path<String>,
+) -> Response {
+    instance_rpc_call(config, &iid, "admin.channels.list", Value::Null).await
+}
+
 // --- Channel Handlers ---
 
-pub async fn channels_list_handler(State(config): State<ServerConfig>) -> Response {
-    rpc_call(config, "admin.channels.list", Value::Null).await
+pub async fn channels_list_handler(State(config): State<ServerConfig>, query: ListQuery) -> Response {
+    list_rpc_call(config, "admin.channels.list", query).await
 }
 
 pub async fn channel_create_handler(
@@ -105,8 +182,8 @@ pub async fn channel_update_handler(
 
 // --- MCP Handlers ---
 
-pub async fn mcp_list_handler(State(config): State<ServerConfig>) -> Response {
-    rpc_call(config, "admin.mcp.list", Value::Null).await
+pub async fn mcp_list_handler(State(config): State<ServerConfig>, query: ListQuery) -> Response {
+    list_rpc_call(config, "admin.mcp.list", query).await
 }
 
 pub async fn mcp_create_handler(
@@ -144,8 +221,8 @@ pub async fn mcp_update_handler(
 
 // --- Policy Handlers ---
 
-pub async fn policy_list_handler(State(config): State<ServerConfig>) -> Response {
-    rpc_call(config, "admin.policies.list", Value::Null).await
+pub async fn policy_list_handler(State(config): State<ServerConfig>, query: ListQuery) -> Response {
+    list_rpc_call(config, "admin.policies.list", query).await
 }
 
 pub async fn policy_create_handler(
@@ -193,12 +270,66 @@ pub async fn policy_delete_handler(
 
 // --- Audit Handlers ---
 
-pub async fn audit_list_handler(State(config): State<ServerConfig>) -> Response {
-    rpc_call(config, "admin.audit.list", Value::Null).await
+pub async fn audit_list_handler(State(config): State<ServerConfig>, query: ListQuery) -> Response {
+    list_rpc_call(config, "admin.audit.list", query).await
 }
 
 // --- Cost Handlers ---
 
-pub async fn cost_summary_handler(State(config): State<ServerConfig>) -> Response {
-    rpc_call(config, "admin.cost.summary", Value::Null).await
+pub async fn cost_summary_handler(State(config): State<ServerConfig>, query: ListQuery) -> Response {
+    list_rpc_call(config, "admin.cost.summary", query).await
+}
+
+// --- Event stream ---
+
+/// Stream server-initiated notifications (e.g. `task.progress`, `cost.updated`,
+/// `audit.appended`) from the sidecar as Server-Sent Events, so the UI can
+/// render live progress without polling `/cost/summary` and friends.
+///
+/// An optional `?methods=task.progress,cost.updated` query param restricts
+/// the stream to the listed methods; omit it to receive everything. Axum's
+/// `Sse::keep_alive` sends a periodic comment frame to keep intermediary
+/// proxies from timing out an idle connection.
+pub async fn events_handler(
+    State(config): State<ServerConfig>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Response> {
+    let Some(sidecar) = config.sidecar else {
+        return Err((StatusCode::SERVICE_UNAVAILABLE, "Sidecar not initialized").into_response());
+    };
+
+    let methods: Option<Vec<String>> = query.get("methods").map(|raw| {
+        raw.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    });
+
+    let stream = BroadcastStream::new(sidecar.subscribe_notifications()).filter_map(move |line| {
+        let line = match line {
+            Ok(line) => line,
+            // A slow subscriber missed some notifications; skip ahead rather
+            // than erroring the whole stream out.
+            Err(tokio_stream::wrappers::errors::BroadcastStreamRecvError::Lagged(_)) => {
+                return None;
+            }
+        };
+
+        let value: Value = serde_json::from_str(&line).ok()?;
+        let method = value.get("method").and_then(|v| v.as_str())?;
+
+        if let Some(methods) = &methods {
+            if !methods.iter().any(|m| m == method) {
+                return None;
+            }
+        }
+
+        Some(Ok(Event::default().event(method).data(line)))
+    });
+
+    Ok(Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("heartbeat"),
+    ))
 }
@@ -0,0 +1,167 @@
+//! Conditional-request (`ETag`/`Last-Modified`) and `Range` handling shared by
+//! every handler that serves a byte buffer with an associated content type and
+//! modification time — originally just `routes::serve_file` for static
+//! assets, now also `media::media_download_handler` for uploaded blobs. Kept
+//! independent of where the bytes actually come from (disk, a blob store) so
+//! neither caller has to duplicate this logic to get the same caching and
+//! resumable-download behavior.
+
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A weak ETag derived from size and mtime rather than content, since hashing
+/// every response on every request would defeat the point of a cache
+/// validator. Weak because two different representations of the same logical
+/// resource (e.g. a precompressed/uncompressed swap) can share one without
+/// claiming byte-for-byte equality.
+pub fn weak_etag(len: u64, modified: SystemTime) -> String {
+    let mtime_secs = modified.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    format!("W/\"{}-{}\"", len, mtime_secs)
+}
+
+fn if_none_match_satisfied(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == "*" || tag.trim() == etag))
+}
+
+fn if_modified_since_satisfied(headers: &HeaderMap, modified: SystemTime) -> bool {
+    let Some(since) = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| httpdate::parse_http_date(v).ok())
+    else {
+        return false;
+    };
+    // `Last-Modified`/`If-Modified-Since` only carry second resolution.
+    let modified_secs = modified.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let since_secs = since.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    modified_secs <= since_secs
+}
+
+/// If `headers` carries a satisfied `If-None-Match`/`If-Modified-Since`,
+/// returns the `304` response to send instead of the body — checked before
+/// a caller does the (possibly expensive) work of reading the actual content.
+pub fn conditional_not_modified(
+    headers: &HeaderMap,
+    len: u64,
+    modified: SystemTime,
+) -> Option<axum::response::Response> {
+    let etag = weak_etag(len, modified);
+    if if_none_match_satisfied(headers, &etag) || if_modified_since_satisfied(headers, modified) {
+        Some(
+            (
+                StatusCode::NOT_MODIFIED,
+                [
+                    (header::ETAG, etag),
+                    (header::LAST_MODIFIED, httpdate::fmt_http_date(modified)),
+                ],
+            )
+                .into_response(),
+        )
+    } else {
+        None
+    }
+}
+
+/// Outcome of matching a request's `Range` header against a known content
+/// length. Only single-range requests are supported, matching what browsers
+/// and download managers actually send for resumable transfers; a
+/// multi-range request just falls back to a full response.
+enum RangeRequest {
+    Full,
+    Partial { start: u64, end: u64 },
+    Unsatisfiable,
+}
+
+fn parse_range(headers: &HeaderMap, len: u64) -> RangeRequest {
+    let Some(raw) = headers.get(header::RANGE).and_then(|v| v.to_str().ok()) else {
+        return RangeRequest::Full;
+    };
+    let Some(spec) = raw.strip_prefix("bytes=") else {
+        return RangeRequest::Full;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Full;
+    }
+    let Some((start_str, end_str)) = spec.split_once('-') else {
+        return RangeRequest::Full;
+    };
+
+    if len == 0 {
+        return RangeRequest::Unsatisfiable;
+    }
+
+    let bounds = if start_str.is_empty() {
+        // Suffix range: the last N bytes of the resource.
+        end_str.parse::<u64>().ok().map(|suffix_len| {
+            let start = len.saturating_sub(suffix_len.max(1));
+            (start, len - 1)
+        })
+    } else {
+        let start = start_str.parse::<u64>().ok();
+        let end = if end_str.is_empty() {
+            Some(len - 1)
+        } else {
+            end_str.parse::<u64>().ok()
+        };
+        start.zip(end).map(|(start, end)| (start, end.min(len - 1)))
+    };
+
+    match bounds {
+        Some((start, end)) if start <= end && start < len => RangeRequest::Partial { start, end },
+        Some(_) => RangeRequest::Unsatisfiable,
+        None => RangeRequest::Full,
+    }
+}
+
+/// Turn an already-read byte buffer into a response honoring conditional and
+/// `Range` requests: full `200`, partial `206`, or `416` if the range doesn't
+/// fit. Callers that can cheaply check freshness before reading the content
+/// (e.g. from filesystem metadata) should call [`conditional_not_modified`]
+/// first and only read+call this on a miss.
+///
+/// `negotiates_encoding` should be `true` whenever the caller picked between
+/// differently-encoded representations of this URL based on the request's
+/// `Accept-Encoding` (e.g. a precompressed `.br`/`.gz` sibling vs. the plain
+/// file), which emits `Vary: Accept-Encoding` so downstream caches don't
+/// serve one client's negotiated representation to another.
+pub fn respond_with_content(
+    content: Vec<u8>,
+    len: u64,
+    modified: SystemTime,
+    content_type: &str,
+    content_encoding: Option<&'static str>,
+    negotiates_encoding: bool,
+    headers: &HeaderMap,
+) -> axum::response::Response {
+    let etag = weak_etag(len, modified);
+    let mut base_headers = vec![
+        (header::CONTENT_TYPE, content_type.to_string()),
+        (header::ETAG, etag),
+        (header::LAST_MODIFIED, httpdate::fmt_http_date(modified)),
+        (header::ACCEPT_RANGES, "bytes".to_string()),
+    ];
+    if let Some(encoding) = content_encoding {
+        base_headers.push((header::CONTENT_ENCODING, encoding.to_string()));
+    }
+    if negotiates_encoding {
+        base_headers.push((header::VARY, "Accept-Encoding".to_string()));
+    }
+
+    match parse_range(headers, len) {
+        RangeRequest::Full => (base_headers, content).into_response(),
+        RangeRequest::Partial { start, end } => {
+            let slice = content[start as usize..=end as usize].to_vec();
+            base_headers.push((header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len)));
+            (StatusCode::PARTIAL_CONTENT, base_headers, slice).into_response()
+        }
+        RangeRequest::Unsatisfiable => (
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            [(header::CONTENT_RANGE, format!("bytes */{}", len))],
+        )
+            .into_response(),
+    }
+}
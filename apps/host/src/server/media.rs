@@ -0,0 +1,216 @@
+//! Media/blob storage for attachments, skill artifacts, and exported reports
+//! the web UI and channels need a place to park, mounted under `/api/media`
+//! in [`super::routes::app_router`].
+//!
+//! Storage is pluggable behind [`BlobStore`], selected by a URI scheme —
+//! today only `file://` (backed by [`FsBlobStore`]) is understood, mirroring
+//! how [`super::ApiAuth`] lets the credential backend vary without touching
+//! the router.
+
+use axum::extract::{Multipart, Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+use thiserror::Error;
+
+use super::http_cache;
+use super::ServerConfig;
+
+const ID_CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Opaque handle to a stored blob, returned from `put` and round-tripped by
+/// the client in `GET`/`DELETE /api/media/:id`. Only alphanumeric so it's
+/// always safe to use as a path segment, including for backends (like
+/// [`FsBlobStore`]) that key storage directly off of it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlobId(pub String);
+
+impl BlobId {
+    fn generate() -> Self {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        let id = (0..32)
+            .map(|_| ID_CHARSET[rng.gen_range(0..ID_CHARSET.len())] as char)
+            .collect();
+        Self(id)
+    }
+
+    fn is_valid(raw: &str) -> bool {
+        !raw.is_empty() && raw.chars().all(|c| c.is_ascii_alphanumeric())
+    }
+}
+
+/// A stored blob's bytes plus the metadata `media_download_handler` needs to
+/// answer conditional and `Range` requests the same way `serve_file` does
+/// for static assets.
+pub struct Blob {
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+    pub modified: SystemTime,
+}
+
+#[derive(Debug, Error)]
+pub enum BlobError {
+    #[error("blob not found")]
+    NotFound,
+    #[error("blob storage error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid blob store URI: {0}")]
+    InvalidUri(String),
+}
+
+/// Storage backend for uploaded media. Implementations only need to honor
+/// `put`/`get`/`delete` for an id they mint themselves in `put` — nothing
+/// upstream assumes a filesystem, so a future S3/GCS-backed store plugs in
+/// the same way [`FsBlobStore`] does.
+#[async_trait::async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, bytes: Vec<u8>, content_type: Option<String>) -> Result<BlobId, BlobError>;
+    async fn get(&self, id: &BlobId) -> Result<Blob, BlobError>;
+    async fn delete(&self, id: &BlobId) -> Result<(), BlobError>;
+}
+
+/// Default backend: each blob as a pair of files under `base_dir`, one for
+/// the bytes and one recording the content type the upload arrived with
+/// (filesystems don't track that themselves, unlike mtime/size).
+pub struct FsBlobStore {
+    base_dir: PathBuf,
+}
+
+impl FsBlobStore {
+    pub fn new(base_dir: PathBuf) -> Self {
+        Self { base_dir }
+    }
+
+    fn data_path(&self, id: &BlobId) -> PathBuf {
+        self.base_dir.join(format!("{}.bin", id.0))
+    }
+
+    fn type_path(&self, id: &BlobId) -> PathBuf {
+        self.base_dir.join(format!("{}.type", id.0))
+    }
+}
+
+fn map_read_err(e: std::io::Error) -> BlobError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        BlobError::NotFound
+    } else {
+        BlobError::Io(e)
+    }
+}
+
+#[async_trait::async_trait]
+impl BlobStore for FsBlobStore {
+    async fn put(&self, bytes: Vec<u8>, content_type: Option<String>) -> Result<BlobId, BlobError> {
+        tokio::fs::create_dir_all(&self.base_dir).await?;
+        let id = BlobId::generate();
+        let content_type = content_type.unwrap_or_else(|| "application/octet-stream".to_string());
+        tokio::fs::write(self.data_path(&id), &bytes).await?;
+        tokio::fs::write(self.type_path(&id), content_type).await?;
+        Ok(id)
+    }
+
+    async fn get(&self, id: &BlobId) -> Result<Blob, BlobError> {
+        let data_path = self.data_path(id);
+        let metadata = tokio::fs::metadata(&data_path).await.map_err(map_read_err)?;
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        let bytes = tokio::fs::read(&data_path).await.map_err(map_read_err)?;
+        let content_type = tokio::fs::read_to_string(self.type_path(id))
+            .await
+            .unwrap_or_else(|_| "application/octet-stream".to_string());
+        Ok(Blob { content_type, bytes, modified })
+    }
+
+    async fn delete(&self, id: &BlobId) -> Result<(), BlobError> {
+        tokio::fs::remove_file(self.data_path(id)).await.map_err(map_read_err)?;
+        // The sidecar `.type` file is best-effort bookkeeping; a missing one
+        // shouldn't make an otherwise-successful delete look like a failure.
+        let _ = tokio::fs::remove_file(self.type_path(id)).await;
+        Ok(())
+    }
+}
+
+/// Build the [`BlobStore`] named by `uri`, dispatching on its scheme. Only
+/// `file://` is understood today; this is the extension point for a future
+/// `s3://`/`gcs://` backend.
+pub fn blob_store_from_uri(uri: &str) -> Result<Arc<dyn BlobStore>, BlobError> {
+    match uri.strip_prefix("file://") {
+        Some(path) => Ok(Arc::new(FsBlobStore::new(PathBuf::from(path)))),
+        None => Err(BlobError::InvalidUri(uri.to_string())),
+    }
+}
+
+pub async fn media_upload_handler(
+    State(config): State<ServerConfig>,
+    mut multipart: Multipart,
+) -> Response {
+    let field = match multipart.next_field().await {
+        Ok(Some(field)) => field,
+        Ok(None) => return (StatusCode::BAD_REQUEST, "No file provided").into_response(),
+        Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid upload: {}", e)).into_response(),
+    };
+
+    let content_type = field.content_type().map(|c| c.to_string());
+    let bytes = match field.bytes().await {
+        Ok(bytes) => bytes.to_vec(),
+        Err(e) => return (StatusCode::BAD_REQUEST, format!("Failed to read upload: {}", e)).into_response(),
+    };
+
+    match config.media.put(bytes, content_type).await {
+        Ok(id) => Json(serde_json::json!({
+            "id": id.0,
+            "url": format!("/api/media/{}", id.0),
+        }))
+        .into_response(),
+        Err(e) => {
+            log::error!("Media upload failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+pub async fn media_download_handler(
+    State(config): State<ServerConfig>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    if !BlobId::is_valid(&id) {
+        return (StatusCode::BAD_REQUEST, "Invalid media id").into_response();
+    }
+
+    match config.media.get(&BlobId(id)).await {
+        Ok(blob) => {
+            let len = blob.bytes.len() as u64;
+            if let Some(not_modified) = http_cache::conditional_not_modified(&headers, len, blob.modified) {
+                return not_modified;
+            }
+            http_cache::respond_with_content(blob.bytes, len, blob.modified, &blob.content_type, None, false, &headers)
+        }
+        Err(BlobError::NotFound) => (StatusCode::NOT_FOUND, "Media not found").into_response(),
+        Err(e) => {
+            log::error!("Media download failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+pub async fn media_delete_handler(
+    State(config): State<ServerConfig>,
+    Path(id): Path<String>,
+) -> Response {
+    if !BlobId::is_valid(&id) {
+        return (StatusCode::BAD_REQUEST, "Invalid media id").into_response();
+    }
+
+    match config.media.delete(&BlobId(id)).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(BlobError::NotFound) => (StatusCode::NOT_FOUND, "Media not found").into_response(),
+        Err(e) => {
+            log::error!("Media delete failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
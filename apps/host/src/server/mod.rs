@@ -1,3 +1,4 @@
+use crate::sidecar::manager::SidecarManager;
 use crate::sidecar::SidecarProcess;
 use axum::Router;
 use std::net::SocketAddr;
@@ -7,10 +8,17 @@ use thiserror::Error;
 
 pub mod auth;
 pub mod handlers;
+pub mod http_cache;
+pub mod media;
+pub mod proxy;
+pub mod query;
 pub mod routes;
 pub mod websocket;
 
+pub use auth::{ApiAuth, AuthContext, AuthError, StaticTokenAuth};
 pub use handlers::{health_handler, skills_handler};
+pub use media::{blob_store_from_uri, BlobError, BlobStore};
+pub use query::{ListQuery, PagedEnvelope};
 pub use routes::app_router;
 pub use websocket::handle_socket;
 
@@ -30,6 +38,31 @@ pub struct ServerConfig {
     pub port: u16,
     pub static_files_path: PathBuf,
     pub sidecar: Option<Arc<SidecarProcess>>,
+    /// Manager for named, multi-instance sidecars, reachable under the
+    /// `/instances/:iid/...` routes. Independent of `sidecar` above, which
+    /// remains the single default instance most routes proxy to.
+    pub sidecars: Option<Arc<SidecarManager>>,
+    /// When set, `static_files_handler`'s fallback serves `index.html` (with
+    /// `200`) instead of `404` for any unmatched path whose final segment
+    /// has no extension, so a client-side router can own deep links like
+    /// `/channels/abc`. Paths that look like assets (a `.` in the final
+    /// segment) still 404 when missing.
+    pub spa_fallback: bool,
+    /// Credential verification backend for `/api` and `/core`, checked by
+    /// `auth::auth_middleware`. Defaults to [`StaticTokenAuth`] with a
+    /// freshly generated token; swap it at construction time (e.g. for
+    /// proxy-header trust or an external verifier) without touching the
+    /// router.
+    pub auth: Arc<dyn ApiAuth>,
+    /// Storage backend for `/api/media`, picked by URI scheme (only
+    /// `file://` today) via [`blob_store_from_uri`]. Defaults to an
+    /// [`media::FsBlobStore`] under a repo-relative directory; point it at
+    /// `file:///var/pryx/media` or similar in production.
+    pub media: Arc<dyn BlobStore>,
+    /// Upper bound on a single `POST /api/media` upload, enforced by a
+    /// `DefaultBodyLimit` layer on that route before the multipart field is
+    /// buffered into memory. Defaults to 25 MiB.
+    pub media_max_upload_bytes: usize,
 }
 
 impl Default for ServerConfig {
@@ -39,6 +72,12 @@ impl Default for ServerConfig {
             port: 42424,
             static_files_path: PathBuf::from("../../local-web/dist"),
             sidecar: None,
+            sidecars: None,
+            spa_fallback: false,
+            auth: Arc::new(StaticTokenAuth::generate()),
+            media: blob_store_from_uri("file://../../local-data/media")
+                .expect("default media store URI is always valid"),
+            media_max_upload_bytes: 25 * 1024 * 1024,
         }
     }
 }
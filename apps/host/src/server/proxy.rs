@@ -0,0 +1,86 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderName, Method, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::server::ServerConfig;
+
+/// Catch-all reverse proxy for any core HTTP endpoint that isn't already
+/// mirrored by a dedicated `/api/...` handler. Forwards method, path, query
+/// string, headers, and body to `http://127.0.0.1:<port>` — the sidecar's
+/// discovered listen port — and streams both the request body and the
+/// response straight through, so the webview only ever needs to know this
+/// one authenticated origin instead of the core's ephemeral port.
+///
+/// Mounted under `/core` behind `auth_middleware`; the admin
+/// `Authorization`/`Cookie` headers are stripped before forwarding and
+/// replaced with the core's own token (if configured), since they authenticate
+/// against the host, not the core.
+pub async fn proxy_handler(State(config): State<ServerConfig>, req: Request) -> Response {
+    let Some(sidecar) = config.sidecar else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "Sidecar not initialized").into_response();
+    };
+    let Some(port) = sidecar.port() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "Core is still starting").into_response();
+    };
+
+    let rest = req
+        .uri()
+        .path()
+        .strip_prefix("/core")
+        .unwrap_or(req.uri().path());
+    let query = req.uri().query().map(|q| format!("?{}", q)).unwrap_or_default();
+    let target = format!("http://127.0.0.1:{}{}{}", port, rest, query);
+
+    let (parts, body) = req.into_parts();
+    let body_stream = body.into_data_stream();
+
+    let client = reqwest::Client::new();
+    let mut builder = client.request(to_reqwest_method(&parts.method), &target);
+
+    for (name, value) in parts.headers.iter() {
+        // These authenticate against the host, not the core; don't leak them
+        // downstream.
+        if name == header::AUTHORIZATION || name == header::COOKIE || name == header::HOST {
+            continue;
+        }
+        if let Ok(value) = value.to_str() {
+            builder = builder.header(name.as_str(), value);
+        }
+    }
+
+    if let Some(token) = sidecar.core_auth_token() {
+        builder = builder.bearer_auth(token);
+    }
+
+    let upstream = match builder.body(reqwest::Body::wrap_stream(body_stream)).send().await {
+        Ok(res) => res,
+        Err(e) => {
+            log::error!("Core proxy request to {} failed: {}", target, e);
+            return (StatusCode::BAD_GATEWAY, "Core did not respond").into_response();
+        }
+    };
+
+    let status = upstream.status();
+    let mut response = Response::builder().status(status.as_u16());
+    for (name, value) in upstream.headers().iter() {
+        if name == header::TRANSFER_ENCODING || name == header::CONNECTION {
+            continue;
+        }
+        response = response.header(HeaderName::from_bytes(name.as_str().as_bytes()).unwrap(), value.as_bytes());
+    }
+
+    let stream = upstream.bytes_stream();
+    response
+        .body(Body::from_stream(stream))
+        .unwrap_or_else(|e| {
+            log::error!("Failed to build proxied response: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Proxy error").into_response()
+        })
+}
+
+fn to_reqwest_method(method: &Method) -> reqwest::Method {
+    reqwest::Method::from_bytes(method.as_str().as_bytes()).unwrap_or(reqwest::Method::GET)
+}
@@ -0,0 +1,157 @@
+//! Shared query-string parsing and pagination for list endpoints
+//! (`/api/channels`, `/api/mcp`, `/api/policies`, `/api/audit/logs`,
+//! `/api/cost/summary`). These proxy a JSON array straight from the core
+//! today with no way to filter, sort, or page it; [`ListQuery`] decodes the
+//! nested/array query-string shape (`filter[provider]=openai&sort=-ts&page=2`)
+//! once, and [`ListQuery::apply`] does the filtering/sorting/pagination in
+//! one place so every handler gets the same semantics and envelope shape.
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+const DEFAULT_PER_PAGE: u32 = 50;
+const MAX_PER_PAGE: u32 = 200;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortSpec {
+    pub field: String,
+    pub descending: bool,
+}
+
+/// Parsed `filter[...]=`, `sort=`, `page=`, and `per_page=` query parameters,
+/// extracted directly from the request URI.
+#[derive(Debug, Clone)]
+pub struct ListQuery {
+    pub filters: HashMap<String, String>,
+    pub sort: Option<SortSpec>,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl Default for ListQuery {
+    fn default() -> Self {
+        Self {
+            filters: HashMap::new(),
+            sort: None,
+            page: 1,
+            per_page: DEFAULT_PER_PAGE,
+        }
+    }
+}
+
+impl ListQuery {
+    /// Parse a raw (undecoded) query string, e.g. the output of
+    /// `uri.query()`. Unknown keys are ignored rather than rejected, since a
+    /// client is free to pass through other query params future endpoints
+    /// might use.
+    pub fn parse(raw: &str) -> Self {
+        let mut query = Self::default();
+
+        let pairs: Vec<(String, String)> =
+            serde_urlencoded::from_str(raw).unwrap_or_default();
+
+        for (key, value) in pairs {
+            if let Some(field) = key.strip_prefix("filter[").and_then(|k| k.strip_suffix(']')) {
+                query.filters.insert(field.to_string(), value);
+            } else if key == "sort" {
+                query.sort = Some(match value.strip_prefix('-') {
+                    Some(field) => SortSpec { field: field.to_string(), descending: true },
+                    None => SortSpec { field: value, descending: false },
+                });
+            } else if key == "page" {
+                if let Ok(page) = value.parse::<u32>() {
+                    query.page = page.max(1);
+                }
+            } else if key == "per_page" {
+                if let Ok(per_page) = value.parse::<u32>() {
+                    query.per_page = per_page.clamp(1, MAX_PER_PAGE);
+                }
+            }
+        }
+
+        query
+    }
+
+    /// Filter, stably sort, and paginate `items`, returning the envelope a
+    /// list handler should respond with.
+    pub fn apply(&self, mut items: Vec<Value>) -> PagedEnvelope {
+        items.retain(|item| {
+            self.filters
+                .iter()
+                .all(|(field, expected)| field_matches(item, field, expected))
+        });
+
+        if let Some(sort) = &self.sort {
+            items.sort_by(|a, b| {
+                let ordering = compare_field(a, b, &sort.field);
+                if sort.descending { ordering.reverse() } else { ordering }
+            });
+        }
+
+        let total = items.len();
+        let start = ((self.page - 1) as usize).saturating_mul(self.per_page as usize);
+        let end = start.saturating_add(self.per_page as usize).min(total);
+        let page_items = if start < total { items[start..end].to_vec() } else { Vec::new() };
+        let next_page = if end < total { Some(self.page + 1) } else { None };
+
+        PagedEnvelope {
+            items: page_items,
+            total,
+            page: self.page,
+            per_page: self.per_page,
+            next_page,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for ListQuery
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::parse(parts.uri.query().unwrap_or("")))
+    }
+}
+
+fn field_matches(item: &Value, field: &str, expected: &str) -> bool {
+    match item.get(field) {
+        Some(Value::String(s)) => s == expected,
+        Some(Value::Bool(b)) => b.to_string() == expected,
+        Some(Value::Number(n)) => n.to_string() == expected,
+        _ => false,
+    }
+}
+
+fn compare_field(a: &Value, b: &Value, field: &str) -> Ordering {
+    let (a, b) = (a.get(field), b.get(field));
+    match (a, b) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(Value::String(a)), Some(Value::String(b))) => a.cmp(b),
+        (Some(Value::Bool(a)), Some(Value::Bool(b))) => a.cmp(b),
+        (Some(a), Some(b)) => a.to_string().cmp(&b.to_string()),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Paginated response envelope returned by every list handler.
+#[derive(Debug, Serialize)]
+pub struct PagedEnvelope {
+    pub items: Vec<Value>,
+    pub total: usize,
+    pub page: u32,
+    pub per_page: u32,
+    pub next_page: Option<u32>,
+}
@@ -1,26 +1,105 @@
 use super::auth::auth_middleware;
 use super::handlers::{
     audit_list_handler, channel_create_handler, channel_delete_handler, channel_get_handler,
-    channel_test_handler, channel_update_handler, channels_list_handler, config_handler,
-    cost_summary_handler, health_handler, mcp_create_handler, mcp_delete_handler, mcp_get_handler,
-    mcp_list_handler, mcp_update_handler, models_handler, policy_create_handler,
-    policy_delete_handler, policy_get_handler, policy_list_handler, policy_update_handler,
-    providers_handler, skills_handler,
+    channel_test_handler, channel_update_handler, channels_list_handler, clients_handler,
+    config_handler, cost_summary_handler, events_handler, health_handler,
+    instance_channels_list_handler, instances_list_handler, mcp_create_handler,
+    mcp_delete_handler, mcp_get_handler, mcp_list_handler, mcp_update_handler, models_handler,
+    policy_create_handler, policy_delete_handler, policy_get_handler, policy_list_handler,
+    policy_update_handler, providers_handler, skills_handler,
 };
+use super::http_cache;
+use super::media::{media_delete_handler, media_download_handler, media_upload_handler};
+use super::proxy;
 use super::websocket::handle_socket;
 use crate::server::ServerConfig;
 use axum::{
-    http::{header, StatusCode},
+    http::{header, HeaderMap, StatusCode},
     middleware,
     response::IntoResponse,
     routing::{get, post},
     Router,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tower_http::services::ServeDir;
 
+/// Precompressed encodings `static_files_handler` will look for next to a
+/// requested file, most-preferred first. Brotli ahead of gzip since it
+/// typically compresses smaller; order here is also the order advertised in
+/// the fallback `Accept-Encoding` preference list.
+const PRECOMPRESSED_ENCODINGS: &[(&str, &str)] = &[("br", "br"), ("gzip", "gz")];
+
+/// Parse an `Accept-Encoding` header value into the subset of
+/// [`PRECOMPRESSED_ENCODINGS`] the client actually accepts (`q=0` excluded),
+/// preserving brotli-before-gzip preference regardless of header order.
+fn accepted_precompressed_encodings(header: &str) -> Vec<&'static str> {
+    let mut accepted = std::collections::HashSet::new();
+    for token in header.split(',') {
+        let mut parts = token.trim().splitn(2, ';');
+        let coding = parts.next().unwrap_or("").trim();
+        let quality: f32 = parts
+            .next()
+            .and_then(|q| q.trim().strip_prefix("q="))
+            .and_then(|q| q.parse().ok())
+            .unwrap_or(1.0);
+        if quality <= 0.0 {
+            continue;
+        }
+        match coding {
+            "*" => {
+                accepted.insert("br");
+                accepted.insert("gzip");
+            }
+            "br" | "gzip" => {
+                accepted.insert(coding);
+            }
+            _ => {}
+        }
+    }
+    PRECOMPRESSED_ENCODINGS
+        .iter()
+        .map(|(coding, _)| *coding)
+        .filter(|coding| accepted.contains(coding))
+        .collect()
+}
+
+/// Read `path` from disk and turn it into a response honoring conditional
+/// (`If-None-Match`/`If-Modified-Since`) and `Range` requests, via the shared
+/// [`http_cache`] helpers. Shared by the precompressed and uncompressed
+/// branches of `static_files_handler` so both get the same caching/
+/// resumability behavior; `content_type` always reflects the *original*
+/// asset, `content_encoding` only the representation actually read off disk.
+async fn serve_file(
+    path: &Path,
+    content_type: &str,
+    content_encoding: Option<&'static str>,
+    headers: &HeaderMap,
+) -> axum::response::Response {
+    let metadata = match tokio::fs::metadata(path).await {
+        Ok(metadata) => metadata,
+        Err(_) => return (StatusCode::NOT_FOUND, "File not found").into_response(),
+    };
+    let len = metadata.len();
+    let modified = metadata.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+
+    if let Some(not_modified) = http_cache::conditional_not_modified(headers, len, modified) {
+        return not_modified;
+    }
+
+    let content = match tokio::fs::read(path).await {
+        Ok(content) => content,
+        Err(e) => {
+            log::error!("Failed to read static file: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to read file").into_response();
+        }
+    };
+
+    http_cache::respond_with_content(content, len, modified, content_type, content_encoding, true, headers)
+}
+
 async fn static_files_handler(
     uri: axum::http::Uri,
+    headers: axum::http::HeaderMap,
     axum::extract::State(config): axum::extract::State<super::ServerConfig>,
 ) -> axum::response::Response {
     // Resolve canonical base directory at startup
@@ -63,26 +142,65 @@ async fn static_files_handler(
         return (StatusCode::FORBIDDEN, "Access denied").into_response();
     }
 
+    // A route with no `.` in its final segment reads as a client-side router
+    // path (`/channels/abc`), not a missing asset; a path that does have one
+    // (`/app.abc123.js`) should keep 404ing on miss so broken asset links
+    // stay visible instead of silently serving the SPA shell.
+    let looks_like_asset = sanitized_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.contains('.'));
+
     // Use async tokio fs operations
     match tokio::fs::metadata(&target_path).await {
         Ok(metadata) => {
             if metadata.is_file() {
-                match tokio::fs::read(&target_path).await {
-                    Ok(content) => {
-                        let mime_type = mime_guess::from_path(&target_path).first_or_octet_stream();
-                        ([("Content-Type", mime_type.as_ref())], content).into_response()
-                    }
-                    Err(e) => {
-                        log::error!("Failed to read static file: {}", e);
-                        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to read file").into_response()
+                let mime_type = mime_guess::from_path(&target_path).first_or_octet_stream();
+                let accept_encoding = headers
+                    .get(header::ACCEPT_ENCODING)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or("");
+
+                // Prefer a precompressed sibling (`app.js.br`/`app.js.gz`) over
+                // the uncompressed file when the client accepts it. We only
+                // ever pass these bytes through as-is; the `Content-Type` still
+                // comes from the *original* path so the browser decompresses
+                // transparently.
+                for encoding in accepted_precompressed_encodings(accept_encoding) {
+                    let suffix = PRECOMPRESSED_ENCODINGS
+                        .iter()
+                        .find(|(coding, _)| *coding == encoding)
+                        .map(|(_, ext)| *ext)
+                        .unwrap_or(encoding);
+                    let mut precompressed_path = target_path.clone().into_os_string();
+                    precompressed_path.push(".");
+                    precompressed_path.push(suffix);
+                    let precompressed_path = PathBuf::from(precompressed_path);
+
+                    if tokio::fs::metadata(&precompressed_path).await.is_ok() {
+                        return serve_file(
+                            &precompressed_path,
+                            mime_type.as_ref(),
+                            Some(encoding),
+                            &headers,
+                        )
+                        .await;
                     }
                 }
+
+                serve_file(&target_path, mime_type.as_ref(), None, &headers).await
             } else {
                 (StatusCode::NOT_FOUND, "File not found").into_response()
             }
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            (StatusCode::NOT_FOUND, "File not found").into_response()
+            if config.spa_fallback && !looks_like_asset {
+                let index_path = base_dir.join("index.html");
+                let mime_type = mime_guess::from_path(&index_path).first_or_octet_stream();
+                serve_file(&index_path, mime_type.as_ref(), None, &headers).await
+            } else {
+                (StatusCode::NOT_FOUND, "File not found").into_response()
+            }
         }
         Err(e) => {
             log::error!("Error checking static file: {}", e);
@@ -98,6 +216,10 @@ pub fn app_router(config: ServerConfig) -> Router {
         .route("/config", get(config_handler))
         .route("/providers", get(providers_handler))
         .route("/models", get(models_handler))
+        .route("/clients", get(clients_handler))
+        // Multi-instance (see `crate::sidecar::manager::SidecarManager`)
+        .route("/instances", get(instances_list_handler))
+        .route("/instances/:iid/channels", get(instance_channels_list_handler))
         // Channels
         .route(
             "/channels",
@@ -132,12 +254,34 @@ pub fn app_router(config: ServerConfig) -> Router {
         // Audit & Cost
         .route("/audit/logs", get(audit_list_handler))
         .route("/cost/summary", get(cost_summary_handler))
+        // Live notification stream
+        .route("/events", get(events_handler))
+        // Media/blob uploads (attachments, skill artifacts, exported reports).
+        // Capped separately from the rest of `/api` since a multipart upload
+        // is the one body this router buffers in full.
+        .route(
+            "/media",
+            post(media_upload_handler)
+                .layer(axum::extract::DefaultBodyLimit::max(config.media_max_upload_bytes)),
+        )
+        .route(
+            "/media/:id",
+            get(media_download_handler).delete(media_delete_handler),
+        )
+        .layer(middleware::from_fn(auth_middleware))
+        .with_state(config.clone());
+
+    let core_proxy_routes = Router::new()
+        .fallback(proxy::proxy_handler)
         .layer(middleware::from_fn(auth_middleware))
         .with_state(config.clone());
 
     Router::new()
         .route("/", get(root_handler))
         .nest("/api", api_routes)
+        // Transparent authenticated reverse proxy to any core HTTP endpoint
+        // not already mirrored under /api.
+        .nest("/core", core_proxy_routes)
         // WS
         .route("/ws", get(ws_upgrade_handler))
         .nest_service("/static", ServeDir::new(&config.static_files_path))
@@ -148,11 +292,7 @@ pub fn app_router(config: ServerConfig) -> Router {
 async fn root_handler(
     axum::extract::State(config): axum::extract::State<ServerConfig>,
 ) -> axum::response::Response {
-    let token = if let Some(sidecar) = config.sidecar {
-        sidecar.admin_token.lock().expect("mutex poisoned").clone()
-    } else {
-        "".to_string()
-    };
+    let token = config.auth.bootstrap_token().unwrap_or_default();
 
     let index_path = config.static_files_path.join("index.html");
     let content = if let Ok(c) = tokio::fs::read(&index_path).await {
@@ -176,6 +316,9 @@ async fn root_handler(
     response
 }
 
-async fn ws_upgrade_handler(ws: axum::extract::ws::WebSocketUpgrade) -> impl IntoResponse {
-    ws.on_upgrade(handle_socket)
+async fn ws_upgrade_handler(
+    ws: axum::extract::ws::WebSocketUpgrade,
+    axum::extract::State(config): axum::extract::State<ServerConfig>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_socket(socket, config))
 }
@@ -1,30 +1,118 @@
-use axum::extract::ws::WebSocket;
-use futures::StreamExt;
-use std::sync::Arc;
-use tokio::sync::broadcast;
+use crate::server::ServerConfig;
+use axum::extract::ws::{Message, WebSocket};
+use futures::{SinkExt, StreamExt};
+use serde_json::Value;
+use tokio::sync::mpsc;
 
-pub struct WsState {
-    pub tx: broadcast::Sender<String>,
-}
+/// Bridge a single frontend WebSocket connection to the running sidecar.
+///
+/// Inbound text frames are parsed as JSON-RPC requests and forwarded to the
+/// sidecar via [`crate::sidecar::SidecarProcess::call_rpc`], with the
+/// response (or a JSON-RPC error frame) written back to the same socket.
+/// Sidecar-originated notifications are fanned out to every connected socket
+/// via [`crate::sidecar::SidecarProcess::subscribe_notifications`].
+pub async fn handle_socket(socket: WebSocket, config: ServerConfig) {
+    let (mut ws_tx, mut ws_rx) = socket.split();
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<String>();
+
+    let Some(sidecar) = config.sidecar.clone() else {
+        let _ = ws_tx
+            .send(Message::Text(
+                rpc_error(None, -32000, "Sidecar not initialized").to_string(),
+            ))
+            .await;
+        return;
+    };
 
-pub async fn handle_socket(mut socket: WebSocket) {
-    while let Some(result) = socket.next().await {
-        match result {
-            Ok(msg) => {
-                if let Ok(text) = msg.to_text() {
-                    println!("WebSocket received: {}", text);
-                    // Echo back
-                    let _ = socket.send(msg).await;
+    // Single writer task: everything destined for the socket (RPC replies
+    // and fanned-out sidecar notifications) funnels through `out_tx`.
+    let writer = tokio::spawn(async move {
+        while let Some(line) = out_rx.recv().await {
+            if ws_tx.send(Message::Text(line)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut notifications = sidecar.subscribe_notifications();
+    let notify_tx = out_tx.clone();
+    let notify_task = tokio::spawn(async move {
+        loop {
+            match notifications.recv().await {
+                Ok(line) => {
+                    if notify_tx.send(line).is_err() {
+                        break;
+                    }
                 }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
             }
+        }
+    });
+
+    while let Some(result) = ws_rx.next().await {
+        let msg = match result {
+            Ok(msg) => msg,
             Err(e) => {
-                eprintln!("WebSocket error: {}", e);
+                log::warn!("WebSocket error: {}", e);
                 break;
             }
-        }
+        };
+
+        let text = match msg {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let value: Value = match serde_json::from_str(&text) {
+            Ok(v) => v,
+            Err(e) => {
+                let _ = out_tx.send(
+                    rpc_error(None, -32700, &format!("Parse error: {}", e)).to_string(),
+                );
+                continue;
+            }
+        };
+
+        let id = value.get("id").cloned();
+        let method = value
+            .get("method")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        let params = value.get("params").cloned().unwrap_or(Value::Null);
+
+        let Some(method) = method else {
+            let _ = out_tx.send(
+                rpc_error(id, -32600, "Invalid request: missing 'method'").to_string(),
+            );
+            continue;
+        };
+
+        let sidecar = sidecar.clone();
+        let out_tx = out_tx.clone();
+        tokio::spawn(async move {
+            let response = match sidecar.call_rpc(&method, params).await {
+                Ok(result) => serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "result": result,
+                    "id": id,
+                }),
+                Err(e) => rpc_error(id, -32000, &e.to_string()),
+            };
+            let _ = out_tx.send(response.to_string());
+        });
     }
+
+    notify_task.abort();
+    drop(out_tx);
+    let _ = writer.await;
 }
 
-pub fn broadcast_message(state: &Arc<WsState>, message: &str) {
-    let _ = state.tx.send(message.to_string());
+fn rpc_error(id: Option<Value>, code: i64, message: &str) -> Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": { "code": code, "message": message },
+        "id": id,
+    })
 }
@@ -0,0 +1,101 @@
+//! Unified settings store shared across Tauri commands.
+//!
+//! Previously each command that needed `PermissionDialogConfig` re-read and
+//! re-parsed `permissions.json` from disk on every invocation. `SettingsStore`
+//! loads it once, keeps it in a lock-free [`ArcSwap`] so reads are cheap and
+//! contention-free, and watches the file on disk so changes made outside the
+//! app (or by a future settings UI writing directly to the file) are picked
+//! up without a restart.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+
+use crate::sidecar::permissions::PermissionDialogConfig;
+
+/// All host-side settings that commands read and write. New settings
+/// sections should be added here rather than introduced as their own
+/// ad hoc config file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub permissions: PermissionDialogConfig,
+}
+
+/// Shared, hot-reloadable handle to `settings.json`.
+pub struct SettingsStore {
+    path: PathBuf,
+    current: Arc<ArcSwap<Settings>>,
+}
+
+impl SettingsStore {
+    /// Load settings from `path`, falling back to defaults if the file is
+    /// missing or fails to parse.
+    pub fn load(path: PathBuf) -> Self {
+        let settings = read_settings(&path).unwrap_or_default();
+        Self {
+            path,
+            current: Arc::new(ArcSwap::from_pointee(settings)),
+        }
+    }
+
+    /// Current settings snapshot. Cheap to call from any command.
+    pub fn get(&self) -> Arc<Settings> {
+        self.current.load_full()
+    }
+
+    /// Persist `settings` to disk and publish it to every holder of this
+    /// store.
+    pub fn save(&self, settings: Settings) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(&settings)?;
+        std::fs::write(&self.path, json)?;
+        self.current.store(Arc::new(settings));
+        Ok(())
+    }
+
+    /// Spawn a background watcher that reloads `settings.json` from disk
+    /// whenever it changes, so edits made while the app is running (e.g. by
+    /// a settings UI, or manually) take effect without a restart.
+    pub fn watch(self: &Arc<Self>) {
+        let store = self.clone();
+        std::thread::spawn(move || {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let mut watcher = match notify::recommended_watcher(tx) {
+                Ok(w) => w,
+                Err(e) => {
+                    log::error!("Failed to start settings file watcher: {}", e);
+                    return;
+                }
+            };
+
+            if let Some(parent) = store.path.parent() {
+                if let Err(e) = watcher.watch(parent, RecursiveMode::NonRecursive) {
+                    log::error!("Failed to watch settings directory: {}", e);
+                    return;
+                }
+            }
+
+            for res in rx {
+                let Ok(event) = res else { continue };
+                if !event.paths.iter().any(|p| p == &store.path) {
+                    continue;
+                }
+                match read_settings(&store.path) {
+                    Some(settings) => {
+                        log::info!("Reloaded settings from disk");
+                        store.current.store(Arc::new(settings));
+                    }
+                    None => log::warn!("Settings file changed but could not be parsed"),
+                }
+            }
+        });
+    }
+}
+
+fn read_settings(path: &Path) -> Option<Settings> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
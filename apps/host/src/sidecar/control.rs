@@ -0,0 +1,164 @@
+//! Out-of-band control endpoint (Unix domain socket on unix, named pipe on
+//! Windows) for an external supervisor or second process to query or command
+//! a running sidecar without disturbing the primary stdio protocol stream.
+//!
+//! Unlike [`super::transport::FramedTransport`], which carries the sidecar's
+//! own RPC traffic over a dedicated socket in place of stdio, this module
+//! accepts connections from *other* processes: each line is a newline-
+//! delimited JSON-RPC request, gated by a bearer token the connecting client
+//! must present first, dispatched through the same [`super::SidecarProcess::handle_rpc`]
+//! used for the stdio channel so both paths share handler dispatch and
+//! response serialization.
+
+use std::path::PathBuf;
+
+use rand::Rng;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use super::{RpcRequest, SidecarProcess};
+
+const TOKEN_CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Generate a random alphanumeric token of `len` characters to gate access to
+/// the control channel. Not a cryptographic secret derivation, just enough
+/// entropy (default length 32, ~190 bits from a 62-symbol alphabet) that a
+/// client can't guess it; the real protection is that it's only ever shared
+/// out-of-band with trusted operators.
+pub fn generate_random_token(len: usize) -> String {
+    let mut rng = rand::thread_rng();
+    (0..len)
+        .map(|_| TOKEN_CHARSET[rng.gen_range(0..TOKEN_CHARSET.len())] as char)
+        .collect()
+}
+
+/// Bind `path` and accept connections for the lifetime of `process`, each
+/// authenticated against `token`. Runs until the listener itself errors
+/// (e.g. the socket/pipe is removed out from under it).
+pub(crate) fn spawn(process: SidecarProcess, path: PathBuf, token: String) {
+    tokio::spawn(async move {
+        if let Err(e) = listen(process, path, token).await {
+            log::error!("Control channel listener failed: {}", e);
+        }
+    });
+}
+
+#[cfg(unix)]
+async fn listen(process: SidecarProcess, path: PathBuf, token: String) -> std::io::Result<()> {
+    use tokio::net::UnixListener;
+
+    let _ = std::fs::remove_file(&path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let listener = UnixListener::bind(&path)?;
+    log::info!("Control channel listening on {:?}", path);
+
+    loop {
+        let (stream, _addr) = listener.accept().await?;
+        let process = process.clone();
+        let token = token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(process, stream, token).await {
+                log::warn!("Control channel connection ended: {}", e);
+            }
+        });
+    }
+}
+
+#[cfg(windows)]
+async fn listen(process: SidecarProcess, path: PathBuf, token: String) -> std::io::Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let pipe_name = path.to_string_lossy().to_string();
+    log::info!("Control channel listening on {}", pipe_name);
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(&pipe_name)?;
+
+    loop {
+        server.connect().await?;
+        let connected = server;
+        server = ServerOptions::new().create(&pipe_name)?;
+
+        let process = process.clone();
+        let token = token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(process, connected, token).await {
+                log::warn!("Control channel connection ended: {}", e);
+            }
+        });
+    }
+}
+
+/// Authenticate the connection's first line against `token`, then dispatch
+/// every subsequent newline-delimited JSON-RPC request through
+/// `process.handle_rpc_with_metrics`, writing each response back on its own
+/// line.
+async fn serve_connection<S>(
+    process: SidecarProcess,
+    stream: S,
+    token: String,
+) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+
+    let Some(auth_line) = lines.next_line().await? else {
+        return Ok(());
+    };
+    let authorized = serde_json::from_str::<serde_json::Value>(&auth_line)
+        .ok()
+        .and_then(|v| v.get("token").and_then(|t| t.as_str()).map(str::to_string))
+        .is_some_and(|presented| presented == token);
+    if !authorized {
+        log::warn!("Rejected control channel connection: bad or missing token");
+        let _ = writer.write_all(b"{\"error\":\"unauthorized\"}\n").await;
+        return Ok(());
+    }
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let req: RpcRequest = match serde_json::from_str(&line) {
+            Ok(req) => req,
+            Err(e) => {
+                let _ = writer
+                    .write_all(format!("{{\"error\":\"invalid request: {}\"}}\n", e).as_bytes())
+                    .await;
+                continue;
+            }
+        };
+        match process.handle_rpc_with_metrics(req).await {
+            Ok(Some(resp)) => {
+                let mut payload = serde_json::to_vec(&resp).unwrap_or_default();
+                payload.push(b'\n');
+                writer.write_all(&payload).await?;
+            }
+            Ok(None) => {}
+            Err(e) => log::error!("Control channel RPC handler failed: {}", e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_random_token_has_requested_length_and_charset() {
+        let token = generate_random_token(32);
+        assert_eq!(token.len(), 32);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn test_generate_random_token_is_not_constant() {
+        let a = generate_random_token(32);
+        let b = generate_random_token(32);
+        assert_ne!(a, b);
+    }
+}
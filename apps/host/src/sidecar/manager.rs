@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tauri::AppHandle;
+
+use super::{SidecarConfig, SidecarProcess, SidecarStatus};
+
+/// Supervises several independent [`SidecarProcess`] instances, each keyed by
+/// an instance id. Where `SidecarProcess` owns one binary/db/monitor loop,
+/// `SidecarManager` lets one host process run several isolated cores side by
+/// side (e.g. one per workspace or profile), each with its own config and
+/// status, while still sharing the single `AppHandle` the host was launched
+/// with.
+#[derive(Clone)]
+pub struct SidecarManager {
+    app_handle: AppHandle,
+    instances: Arc<Mutex<HashMap<String, Arc<SidecarProcess>>>>,
+}
+
+impl SidecarManager {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            app_handle,
+            instances: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Create and start a new instance under `id`, replacing any previous
+    /// instance registered under the same id (the caller is responsible for
+    /// shutting that one down first if it's still running).
+    pub fn spawn(&self, id: impl Into<String>, config: SidecarConfig) -> Arc<SidecarProcess> {
+        let id = id.into();
+        let process = Arc::new(SidecarProcess::new(config, self.app_handle.clone()));
+
+        {
+            let mut instances = self.instances.lock().unwrap();
+            instances.insert(id.clone(), process.clone());
+        }
+
+        let monitored = process.clone();
+        tokio::spawn(async move {
+            if let Err(e) = monitored.start().await {
+                log::error!("Instance '{}' failed to start: {:?}", id, e);
+            }
+            monitored.monitor().await;
+        });
+
+        process
+    }
+
+    /// Look up a running instance by id.
+    pub fn get(&self, id: &str) -> Option<Arc<SidecarProcess>> {
+        self.instances.lock().unwrap().get(id).cloned()
+    }
+
+    /// Clear a tripped circuit breaker on instance `id` and resume
+    /// supervision, respawning the `start`/`monitor` task that
+    /// `monitor()` exited when the breaker tripped. No-op (returns `false`)
+    /// if `id` isn't known.
+    pub fn reset(&self, id: &str) -> bool {
+        let Some(process) = self.instances.lock().unwrap().get(id).cloned() else {
+            return false;
+        };
+        process.reset_circuit_breaker();
+
+        let id = id.to_string();
+        let monitored = process;
+        tokio::spawn(async move {
+            if let Err(e) = monitored.start().await {
+                log::error!("Instance '{}' failed to restart after reset: {:?}", id, e);
+            }
+            monitored.monitor().await;
+        });
+
+        true
+    }
+
+    /// Shut down and deregister a single instance. No-op if `id` isn't known.
+    pub async fn shutdown(&self, id: &str) {
+        let process = { self.instances.lock().unwrap().remove(id) };
+        if let Some(process) = process {
+            process.shutdown().await;
+        }
+    }
+
+    /// Shut down and deregister every instance.
+    pub async fn shutdown_all(&self) {
+        let processes: Vec<Arc<SidecarProcess>> = {
+            let mut instances = self.instances.lock().unwrap();
+            instances.drain().map(|(_, p)| p).collect()
+        };
+        for process in processes {
+            process.shutdown().await;
+        }
+    }
+
+    /// Snapshot the status of every registered instance, keyed by instance id.
+    pub fn status_all(&self) -> HashMap<String, SidecarStatus> {
+        self.instances
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, process)| (id.clone(), process.status()))
+            .collect()
+    }
+}
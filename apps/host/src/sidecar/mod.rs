@@ -1,36 +1,197 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet, VecDeque},
+    io::{Read, Write},
     path::{Path, PathBuf},
-    process::Stdio,
-    sync::{Arc, Mutex},
+    sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex},
     time::{Duration, Instant},
 };
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 use tauri_plugin_notification::NotificationExt;
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_updater::UpdaterExt;
 use tokio::sync::Mutex as AsyncMutex;
-use tokio::{
-    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
-    process::{Child, ChildStdin, Command},
-};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 
+use arc_swap::ArcSwap;
+use base64::Engine;
+use futures::{Stream, StreamExt};
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+mod control;
+pub mod manager;
 pub mod permissions;
+pub mod spawner;
+pub mod transport;
 #[cfg(test)]
 mod tests;
 use serde_json::Value;
+use spawner::{ChildHandle, ProcessSpawner, ProcessSpec, RealProcessSpawner};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
+use transport::{
+    parse_content_length, Frame, FrameCodec, FramedTransport, IpcTransport, StdioTransport,
+    TcpTransport, Transport, WireCodec,
+};
+
+/// Inclusive `(min, max)` protocol-version range understood by one side of the
+/// host/sidecar link.
+pub type VersionRange = (u32, u32);
+
+/// Protocol-version range this build of `pryx-host` understands. Bump `max`
+/// when the host gains support for a newer sidecar protocol, and `min` only
+/// once support for the oldest protocol is dropped.
+pub const HOST_PROTOCOL_RANGE: VersionRange = (1, 3);
+
+/// Feature flags this host build knows how to handle, advertised during the
+/// `initialize` handshake so the sidecar can tell which optional behavior is
+/// safe to use.
+const HOST_FEATURES: &[&str] = &["clipboard", "notifications", "updater", "pubsub", "process"];
+
+/// Capacity of the bounded queue between the stdout read loop and the
+/// permission dispatcher. Requests beyond this back up briefly; once full,
+/// new ones are auto-denied rather than applying backpressure to the reader.
+const PERMISSION_QUEUE_CAPACITY: usize = 32;
+
+/// Maximum number of `permission.request` dialogs allowed outstanding at
+/// once. Requests beyond this are auto-denied instead of queued.
+const MAX_IN_FLIGHT_PERMISSION_REQUESTS: usize = 4;
 
 /// Sidecar process state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SidecarState {
     Stopped,
     Starting,
+    /// The transport is up (port discovered, or a socket/pipe connected)
+    /// and the LSP-style `initialize`/`initialized` handshake is in
+    /// flight. Sits between `Starting` and `Running` so a caller watching
+    /// state transitions can tell "process exists" apart from "process
+    /// exists and the core has actually negotiated a usable protocol".
+    Initializing,
     Running,
     Crashed { attempts: u32 },
     Restarting { backoff_ms: u64 },
     Stopping,
+    /// The sidecar replied to the `initialize` handshake with a protocol
+    /// range that doesn't overlap ours. The process is left running (so logs
+    /// remain visible) but is not considered usable, and the supervisor does
+    /// not attempt to restart it.
+    Incompatible {
+        host_range: VersionRange,
+        core_range: VersionRange,
+    },
+}
+
+/// Classification of a child's `ExitStatus`, distinguishing an intentional
+/// shutdown from an actual crash so `monitor()` knows whether to restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SidecarExitReason {
+    /// Exited with code 0.
+    CleanExit,
+    /// Exited with a nonzero code.
+    ExitCode { code: i32 },
+    /// Killed by SIGTERM or SIGKILL — the same signals `stop()` sends —
+    /// treated as an intentional shutdown rather than a crash.
+    Stopped { signal: i32 },
+    /// Killed by any other signal.
+    Signaled { signal: i32 },
+    /// The exit status couldn't be classified on this platform.
+    Unknown,
+    /// Killed by the host after `health_probe_failure_threshold` consecutive
+    /// `health.ping` failures: the process never actually exited on its
+    /// own, it was alive but unresponsive. Counts as a crash.
+    Unresponsive,
+}
+
+impl SidecarExitReason {
+    /// Whether this exit should count toward crash/backoff/circuit-breaker
+    /// accounting, as opposed to an intentional shutdown that should just be
+    /// left stopped.
+    fn is_crash(self) -> bool {
+        !matches!(
+            self,
+            SidecarExitReason::CleanExit | SidecarExitReason::Stopped { .. }
+        )
+    }
+
+    fn classify(status: std::process::ExitStatus) -> Self {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            if let Some(signal) = status.signal() {
+                return match signal {
+                    libc::SIGTERM | libc::SIGKILL => SidecarExitReason::Stopped { signal },
+                    _ => SidecarExitReason::Signaled { signal },
+                };
+            }
+        }
+        match status.code() {
+            Some(0) => SidecarExitReason::CleanExit,
+            Some(code) => SidecarExitReason::ExitCode { code },
+            None => SidecarExitReason::Unknown,
+        }
+    }
+}
+
+/// How `monitor()` spaces out restart attempts after a crash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackoffStrategy {
+    /// `random(0, min(max_backoff_ms, initial_backoff_ms *
+    /// backoff_multiplier^(attempt-1)))`. Jittering the whole range, rather
+    /// than just adding noise around the ladder value, avoids restart storms
+    /// when many instances crash at once.
+    #[default]
+    FullJitter,
+    /// `next = min(max_backoff_ms, random(initial_backoff_ms, prev *
+    /// backoff_multiplier))`, with `prev` seeded to `initial_backoff_ms` and
+    /// carried forward from the previous attempt. Converges to a steadier
+    /// retry rate than full
+    /// jitter under sustained crash-looping, without the lockstep of plain
+    /// exponential backoff. See the "Exponential Backoff And Jitter" AWS
+    /// Architecture Blog post for the derivation.
+    DecorrelatedJitter,
+}
+
+/// Which [`Transport`] impl carries JSON-RPC between host and sidecar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcTransportKind {
+    /// Newline-delimited JSON multiplexed onto the sidecar's stdin/stdout,
+    /// alongside its diagnostic log lines.
+    #[default]
+    Stdio,
+    /// Length-prefixed frames over a dedicated Unix domain socket / named
+    /// pipe, kept separate from stdout logging. See [`FramedTransport`]. The
+    /// host binds the socket before spawning and the core connects in.
+    Framed,
+    /// Like [`Self::Framed`], but the core binds the socket itself and
+    /// advertises its path via a `PRYX_CORE_IPC_PATH=` stdout line; the host
+    /// dials in instead of pre-allocating a path and waiting to accept. See
+    /// [`IpcTransport`].
+    Ipc,
+}
+
+/// Whether a [`SidecarProcess`] owns a child it launches itself, or attaches
+/// to a core already running elsewhere (under a debugger, or on another
+/// host). Orthogonal to [`RpcTransportKind`], which only governs how a
+/// locally-spawned child's RPC is carried — an attached core always speaks
+/// framed JSON-RPC over its TCP socket.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "mode")]
+pub enum SidecarSpawnMode {
+    /// Launch and own a local child process. The default, and the only mode
+    /// `monitor()`'s crash/backoff/circuit-breaker path applies to.
+    #[default]
+    Spawn,
+    /// Connect to a core already listening on `addr` (the core honors
+    /// `PRYX_LISTEN_ADDR` the same way a spawned child does) instead of
+    /// launching one. `monitor()` health-checks the connection and
+    /// reconnects with the same backoff logic rather than restarting a
+    /// process, and [`SidecarStatus::pid`] reports `None`.
+    Attach { addr: String },
 }
 
 /// Sidecar configuration
@@ -45,6 +206,73 @@ pub struct SidecarConfig {
     pub max_restarts: u32,
     pub initial_backoff_ms: u64,
     pub backoff_multiplier: f64,
+    /// How long to wait for a port to appear in the sidecar's stdout before
+    /// falling back to scanning the OS socket table for a listening port
+    /// owned by the child PID.
+    pub port_discovery_timeout_secs: u64,
+    /// How long to wait for a user decision on an inbound `permission.request`
+    /// before auto-resolving it to `PermissionOutcome::TimedOut`.
+    pub permission_dialog_timeout_ms: u64,
+    /// Bearer token the core expects on its own HTTP endpoints, if any.
+    /// Distinct from the host's admin token: this one is injected by the
+    /// reverse proxy when forwarding requests on to the core.
+    pub core_auth_token: Option<String>,
+    /// Ceiling for the jittered restart backoff, regardless of how high the
+    /// exponential ladder (`initial_backoff_ms * backoff_multiplier^attempt`)
+    /// would otherwise climb.
+    pub max_backoff_ms: u64,
+    /// Which jitter algorithm `monitor()` uses to space out restart attempts.
+    pub backoff_strategy: BackoffStrategy,
+    /// Number of restarts within `circuit_breaker_window_secs` that trips the
+    /// circuit breaker and halts auto-restart until an explicit reset.
+    pub circuit_breaker_threshold: u32,
+    /// Sliding window, in seconds, over which restarts are counted toward
+    /// `circuit_breaker_threshold`.
+    pub circuit_breaker_window_secs: u64,
+    /// Which [`Transport`] impl to spawn the sidecar with.
+    pub rpc_transport: RpcTransportKind,
+    /// How [`StdioTransport`] delimits messages when `rpc_transport` is
+    /// [`RpcTransportKind::Stdio`]. Ignored by [`FramedTransport`] and
+    /// [`IpcTransport`], which are always length-prefixed. Defaults to
+    /// [`FrameCodec::ContentLength`],
+    /// which frames by a declared byte count instead of guessing from a
+    /// line's content, so a log line that happens to start with `{` (or a
+    /// pretty-printed/multi-line payload) can never be mistaken for RPC.
+    /// `LineDelimited` remains available for a sidecar binary that hasn't
+    /// been updated to emit `Content-Length` headers yet.
+    pub frame_codec: FrameCodec,
+    /// How a frame's payload bytes are serialized — JSON by default, or one
+    /// of the binary formats for high-frequency core traffic. Announced to
+    /// the core in the `initialize` handshake; both sides must be
+    /// configured to speak the same codec, since it isn't renegotiated.
+    /// Pairing a binary codec with [`FrameCodec::LineDelimited`] is a
+    /// misconfiguration: a binary payload can contain an embedded `\n` that
+    /// framing would mistake for a message boundary.
+    pub wire_codec: WireCodec,
+    /// How often `monitor()` issues an `health.ping` liveness probe against
+    /// a `Running` sidecar, on top of the passive `try_wait` dead-process
+    /// check. Probing is the only way to catch a hung-but-alive process.
+    pub health_probe_interval_secs: u64,
+    /// Per-probe timeout; shorter than `call_rpc`'s default 10s so a single
+    /// stuck probe doesn't stall the whole interval.
+    pub health_probe_timeout_ms: u64,
+    /// Consecutive probe failures before the sidecar is treated as
+    /// unhealthy and killed, re-entering the normal crash/backoff/restart
+    /// path as if the process had died on its own.
+    pub health_probe_failure_threshold: u32,
+    /// Path to an out-of-band control endpoint (Unix domain socket; named
+    /// pipe on Windows) that accepts the same JSON-RPC methods as the
+    /// primary stdio link, gated by [`SidecarProcess::control_token`].
+    /// `None` (the default) disables the control channel entirely.
+    pub control_socket_path: Option<PathBuf>,
+    /// Budget for a single `handle_rpc` dispatch, e.g. `updater.install` or
+    /// the blocking permission dialog, so a stuck handler fails that one
+    /// call with [`RpcError::INTERNAL_ERROR`] rather than wedging the reader
+    /// loop every other request is waiting behind.
+    pub rpc_handler_timeout_ms: u64,
+    /// Whether to spawn a local child or attach to a remote core. Defaults
+    /// to [`SidecarSpawnMode::Spawn`], the existing behavior.
+    pub spawn_mode: SidecarSpawnMode,
 }
 
 impl SidecarConfig {
@@ -59,6 +287,22 @@ impl SidecarConfig {
             max_restarts: 10,
             initial_backoff_ms: 1000,
             backoff_multiplier: 2.0,
+            port_discovery_timeout_secs: 1,
+            permission_dialog_timeout_ms: 30_000,
+            core_auth_token: None,
+            max_backoff_ms: 30_000,
+            backoff_strategy: BackoffStrategy::default(),
+            circuit_breaker_threshold: 5,
+            circuit_breaker_window_secs: 60,
+            rpc_transport: RpcTransportKind::default(),
+            frame_codec: FrameCodec::default(),
+            wire_codec: WireCodec::default(),
+            health_probe_interval_secs: 10,
+            health_probe_timeout_ms: 2_000,
+            health_probe_failure_threshold: 3,
+            control_socket_path: None,
+            rpc_handler_timeout_ms: 30_000,
+            spawn_mode: SidecarSpawnMode::default(),
         }
     }
 }
@@ -73,6 +317,29 @@ impl Default for SidecarConfig {
     }
 }
 
+/// Outcome of an inbound `permission.request`, reported back to the sidecar
+/// so it can distinguish a deliberate denial from a dismissed dialog or a
+/// timeout rather than seeing a bare `approved: false`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionOutcome {
+    Approved,
+    Denied,
+    TimedOut,
+    /// The dialog task itself panicked instead of resolving — e.g. the
+    /// native dialog backend crashed — as opposed to a user decision.
+    /// `tauri_plugin_dialog`'s `blocking_show()` only ever returns a
+    /// `bool`, so this is the only non-timeout failure mode `spawn_blocking`
+    /// can surface here.
+    Error,
+}
+
+impl PermissionOutcome {
+    fn approved(self) -> bool {
+        matches!(self, PermissionOutcome::Approved)
+    }
+}
+
 /// RPC Request from Sidecar
 #[derive(Debug, Deserialize)]
 struct RpcRequest {
@@ -83,39 +350,360 @@ struct RpcRequest {
     id: u64,
 }
 
-/// RPC Response to Sidecar
+/// RPC Response to Sidecar. Per JSON-RPC 2.0, a response carries exactly one
+/// of `result`/`error`, never both, so both are `Option`s and omitted from
+/// the wire when absent via `skip_serializing_if`.
 #[derive(Debug, Serialize)]
 struct RpcResponse {
     jsonrpc: String,
-    result: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
     id: u64,
 }
 
+impl RpcResponse {
+    fn success(id: u64, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result: Some(result),
+            error: None,
+            id,
+        }
+    }
+
+    fn error(id: u64, error: RpcError) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(error),
+            id,
+        }
+    }
+}
+
+/// A JSON-RPC 2.0 error object. `-32700..-32600` are the spec-reserved
+/// codes; `-32000..-32099` is the spec's "server error" range, which this
+/// crate uses for application-specific failures like an update check
+/// finding nothing or the updater failing to initialize.
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i32,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+}
+
+impl RpcError {
+    const INVALID_REQUEST: i32 = -32600;
+    const METHOD_NOT_FOUND: i32 = -32601;
+    const INVALID_PARAMS: i32 = -32602;
+    const INTERNAL_ERROR: i32 = -32603;
+    const UPDATE_NOT_FOUND: i32 = -32000;
+    const UPDATER_INIT_FAILED: i32 = -32001;
+    const UPDATE_CANCELLED: i32 = -32002;
+
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(Self::INVALID_REQUEST, message)
+    }
+
+    fn method_not_found(method: &str) -> Self {
+        Self::new(Self::METHOD_NOT_FOUND, format!("Method not found: {}", method))
+    }
+
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(Self::INVALID_PARAMS, message)
+    }
+
+    fn internal(message: impl Into<String>) -> Self {
+        Self::new(Self::INTERNAL_ERROR, message)
+    }
+}
+
+/// A child process launched on the sidecar's behalf via `process.spawn`,
+/// backed by a PTY (same approach as [`crate::pty::PtyManager`]) so the
+/// sidecar can drive genuinely interactive commands rather than plain pipes.
+struct SpawnedProcess {
+    master: Box<dyn portable_pty::MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    child: Box<dyn portable_pty::Child + Send + Sync>,
+}
+
+impl std::fmt::Debug for SpawnedProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpawnedProcess").finish_non_exhaustive()
+    }
+}
+
+/// Lifecycle signal for the update download started by `updater.install`,
+/// checked between each downloaded chunk so `updater.pause`/`updater.resume`/
+/// `updater.cancel` can steer the in-flight task rather than tearing it down
+/// and losing the bytes fetched so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DownloadControl {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+/// Resumable-download bookkeeping for the update currently being fetched,
+/// persisted alongside the partial download itself so a host restart
+/// mid-download can pick the HTTP Range request back up instead of starting
+/// over. Keyed on `url` so a stale record for a since-superseded release
+/// isn't mistaken for a resumable one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UpdateDownloadState {
+    url: String,
+    total_len: Option<u64>,
+    bytes_so_far: u64,
+}
+
+/// Registry of sidecar-side interest in specific notification topics,
+/// populated by `pubsub.subscribe`/`pubsub.unsubscribe` so [`SidecarProcess::publish`]
+/// can fan a topic out only to callers that asked for it rather than every
+/// `send_notification` consumer on the link.
+#[derive(Debug, Default)]
+struct Subscriptions {
+    by_topic: HashMap<String, HashSet<u64>>,
+    topic_of: HashMap<u64, String>,
+}
+
+impl Subscriptions {
+    fn subscribe(&mut self, topic: &str, sub_id: u64) {
+        self.by_topic.entry(topic.to_string()).or_default().insert(sub_id);
+        self.topic_of.insert(sub_id, topic.to_string());
+    }
+
+    /// Remove `sub_id` from its topic, returning whether it was actually
+    /// subscribed to anything.
+    fn unsubscribe(&mut self, sub_id: u64) -> bool {
+        match self.topic_of.remove(&sub_id) {
+            Some(topic) => {
+                if let Some(subs) = self.by_topic.get_mut(&topic) {
+                    subs.remove(&sub_id);
+                    if subs.is_empty() {
+                        self.by_topic.remove(&topic);
+                    }
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn subscribers(&self, topic: &str) -> impl Iterator<Item = &u64> {
+        self.by_topic.get(topic).into_iter().flatten()
+    }
+}
+
 /// Sidecar process information
 #[derive(Debug, Clone)]
 pub struct SidecarProcess {
     config: SidecarConfig,
-    state: Arc<Mutex<SidecarState>>,
-    child: Arc<Mutex<Option<Child>>>,
+    /// Lock-free so `status()` can be polled at high frequency (e.g. from a
+    /// UI refresh loop) without contending with the monitor loop's writes.
+    state: Arc<ArcSwap<SidecarState>>,
+    child: Arc<Mutex<Option<Box<dyn ChildHandle>>>>,
     port: Arc<Mutex<Option<u16>>>,
     start_time: Arc<Mutex<Option<Instant>>>,
     crash_count: Arc<Mutex<u32>>,
-    stdin: Arc<AsyncMutex<Option<ChildStdin>>>,
+    /// Timestamps of recent restarts, trimmed to `circuit_breaker_window_secs`
+    /// on every crash; used only to decide whether the circuit breaker trips.
+    restart_times: Arc<Mutex<VecDeque<Instant>>>,
+    /// Running `prev` cursor for [`BackoffStrategy::DecorrelatedJitter`],
+    /// seeded to `initial_backoff_ms` and reset by
+    /// [`SidecarProcess::reset_backoff`] after a stable reconnect. Unused
+    /// under [`BackoffStrategy::FullJitter`].
+    backoff_prev_ms: Arc<Mutex<u64>>,
+    /// Set once the circuit breaker trips. While `true`, `monitor()` has
+    /// returned and will not restart the process until
+    /// [`SidecarProcess::reset_circuit_breaker`] is called.
+    circuit_open: Arc<Mutex<bool>>,
+    /// Human-readable reason for the most recent crash or health-check
+    /// failure, surfaced on `SidecarStatus` for diagnostics.
+    last_error: Arc<Mutex<Option<String>>>,
+    /// Aggregate reliability counters, updated as each [`MetricsGuard`] drops.
+    metrics: Arc<Mutex<SidecarMetrics>>,
+    /// Guard for the currently running child, if any. Replacing it in
+    /// `spawn_sidecar` drops the previous guard, recording that run's
+    /// duration and whether it was disarmed by a clean `stop()`.
+    metrics_guard: Arc<Mutex<Option<MetricsGuard>>>,
+    /// Numeric exit code of the last child exit, if it exited rather than
+    /// being signaled.
+    last_exit_code: Arc<Mutex<Option<i32>>>,
+    /// Terminating signal of the last child exit, on Unix, if any.
+    last_signal: Arc<Mutex<Option<i32>>>,
+    /// Classification of the last child exit; see [`SidecarExitReason`].
+    last_exit_reason: Arc<Mutex<Option<SidecarExitReason>>>,
+    stdin: Arc<AsyncMutex<Option<Box<dyn tokio::io::AsyncWrite + Send + Unpin>>>>,
+    /// Active RPC transport, set once `spawn_sidecar` brings one up.
+    /// `call_rpc`/`subscribe`/`send_response`/`send_notification` all go
+    /// through this instead of writing `stdin` directly, so they work
+    /// unchanged regardless of `SidecarConfig::rpc_transport`.
+    transport: Arc<AsyncMutex<Option<Arc<dyn Transport>>>>,
     app_handle: Arc<Mutex<Option<AppHandle>>>,
+    protocol_version: Arc<Mutex<Option<u32>>>,
+    capabilities: Arc<Mutex<Vec<String>>>,
+    pending_requests: Arc<AsyncMutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>>,
+    /// Routing table for [`Self::subscribe`] calls: unlike `pending_requests`,
+    /// entries here aren't removed on the first matching frame, since a
+    /// streaming subscription expects several `{"id": N, "result": ...}`
+    /// frames before a terminator (`result.done == true`) removes it.
+    stream_requests: Arc<AsyncMutex<HashMap<u64, mpsc::Sender<Value>>>>,
+    /// Routing table for core-initiated event subscriptions (progress events,
+    /// log tails, indexing updates): unlike `stream_requests`, which
+    /// correlates by the *request* id a streaming `call_rpc` allocated, these
+    /// are notifications (no `id` of their own) tagged with a
+    /// `params.subscription` string the core chose, keyed here by that same
+    /// string. Populated by [`Self::core_subscribe`], consulted by the
+    /// transport read loop.
+    core_subscriptions: Arc<AsyncMutex<HashMap<String, mpsc::UnboundedSender<Value>>>>,
+    /// Monotonic id allocator for host-initiated requests. A plain atomic
+    /// rather than a mutex-guarded counter since allocating an id is the one
+    /// step of issuing a call that never needs to await anything else.
+    next_rpc_id: Arc<AtomicU64>,
+    /// Fan-out channel for sidecar-originated notifications (JSON-RPC lines
+    /// with a `method` but no `id`), consumed by the WebSocket bridge.
+    notifications: broadcast::Sender<String>,
+    /// Cache of remembered `permission.request` decisions, keyed by a hash of
+    /// `(method, intent, resource)`. Populated when a request carries
+    /// `"remember": true`; consulted before showing a dialog so repeat
+    /// requests for the same scope auto-resolve.
+    permission_decisions: Arc<Mutex<HashMap<String, PermissionOutcome>>>,
+    /// Bounded handoff from the stdout read loop to the permission
+    /// dispatcher, so a slow dialog never stalls line parsing.
+    permission_queue_tx: mpsc::Sender<RpcRequest>,
+    /// Caps how many `permission.request` dialogs can be outstanding at
+    /// once; requests beyond the cap are auto-denied.
+    permission_in_flight: Arc<tokio::sync::Semaphore>,
+    /// Children launched via `process.spawn`, keyed by the id returned to
+    /// the sidecar in that RPC's response.
+    spawned_processes: Arc<Mutex<HashMap<u64, SpawnedProcess>>>,
+    next_process_id: Arc<Mutex<u64>>,
+    /// Launches the sidecar binary. Always [`RealProcessSpawner`] outside of
+    /// tests; [`SidecarProcess::new_with_spawner`] substitutes a
+    /// `MockProcessSpawner` so `monitor()`'s restart/backoff loop and
+    /// `call_rpc`'s timeout paths can be exercised without a real binary.
+    spawner: Arc<dyn ProcessSpawner>,
+    /// When `monitor()` last issued a `health.ping` probe against the
+    /// running child; gates the probe on `health_probe_interval_secs`
+    /// rather than firing one every loop tick.
+    last_probe_at: Arc<Mutex<Option<Instant>>>,
+    /// Result of the most recent liveness probe, surfaced on
+    /// `SidecarStatus` for diagnostics. `true` before the first probe runs.
+    last_health_ok: Arc<Mutex<bool>>,
+    /// Consecutive probe failures; reset to 0 on the first success.
+    /// Reaching `health_probe_failure_threshold` kills the child.
+    consecutive_probe_failures: Arc<Mutex<u32>>,
+    /// Pause/resume/cancel signal for the `updater.install` download
+    /// currently in flight, if any. Reset to `Running` at the start of
+    /// every install.
+    update_control: Arc<Mutex<DownloadControl>>,
+    /// Topic interest registered via `pubsub.subscribe`/`pubsub.unsubscribe`,
+    /// consulted by [`Self::publish`].
+    subscriptions: Arc<Mutex<Subscriptions>>,
+    next_subscription_id: Arc<Mutex<u64>>,
+    /// Token gating the out-of-band control channel, generated once when
+    /// `config.control_socket_path` is set. `None` if the control channel
+    /// is disabled.
+    control_token: Arc<Mutex<Option<String>>>,
 }
 
 impl SidecarProcess {
     pub fn new(config: SidecarConfig, app_handle: AppHandle) -> Self {
-        Self {
+        Self::new_with_spawner(config, app_handle, Arc::new(RealProcessSpawner))
+    }
+
+    /// Like [`Self::new`], but with the process spawner injected. Exposed
+    /// outside `#[cfg(test)]` (rather than gated behind it) so an embedder
+    /// could substitute its own `ProcessSpawner`, but `monitor()`/`stop()`
+    /// tests are the only current caller.
+    pub fn new_with_spawner(
+        config: SidecarConfig,
+        app_handle: AppHandle,
+        spawner: Arc<dyn ProcessSpawner>,
+    ) -> Self {
+        let (permission_queue_tx, permission_queue_rx) = mpsc::channel(PERMISSION_QUEUE_CAPACITY);
+        let initial_backoff_ms = config.initial_backoff_ms;
+
+        let process = Self {
             config,
-            state: Arc::new(Mutex::new(SidecarState::Stopped)),
+            state: Arc::new(ArcSwap::from_pointee(SidecarState::Stopped)),
             child: Arc::new(Mutex::new(None)),
             port: Arc::new(Mutex::new(None)),
             start_time: Arc::new(Mutex::new(None)),
             crash_count: Arc::new(Mutex::new(0)),
+            restart_times: Arc::new(Mutex::new(VecDeque::new())),
+            backoff_prev_ms: Arc::new(Mutex::new(initial_backoff_ms)),
+            circuit_open: Arc::new(Mutex::new(false)),
+            last_error: Arc::new(Mutex::new(None)),
+            metrics: Arc::new(Mutex::new(SidecarMetrics::default())),
+            metrics_guard: Arc::new(Mutex::new(None)),
+            last_exit_code: Arc::new(Mutex::new(None)),
+            last_signal: Arc::new(Mutex::new(None)),
+            last_exit_reason: Arc::new(Mutex::new(None)),
             stdin: Arc::new(AsyncMutex::new(None)),
+            transport: Arc::new(AsyncMutex::new(None)),
             app_handle: Arc::new(Mutex::new(Some(app_handle))),
+            protocol_version: Arc::new(Mutex::new(None)),
+            capabilities: Arc::new(Mutex::new(Vec::new())),
+            pending_requests: Arc::new(AsyncMutex::new(HashMap::new())),
+            stream_requests: Arc::new(AsyncMutex::new(HashMap::new())),
+            core_subscriptions: Arc::new(AsyncMutex::new(HashMap::new())),
+            next_rpc_id: Arc::new(AtomicU64::new(1)),
+            notifications: broadcast::channel(256).0,
+            permission_decisions: Arc::new(Mutex::new(HashMap::new())),
+            permission_queue_tx,
+            permission_in_flight: Arc::new(tokio::sync::Semaphore::new(
+                MAX_IN_FLIGHT_PERMISSION_REQUESTS,
+            )),
+            spawned_processes: Arc::new(Mutex::new(HashMap::new())),
+            next_process_id: Arc::new(Mutex::new(1)),
+            spawner,
+            last_probe_at: Arc::new(Mutex::new(None)),
+            last_health_ok: Arc::new(Mutex::new(true)),
+            consecutive_probe_failures: Arc::new(Mutex::new(0)),
+            update_control: Arc::new(Mutex::new(DownloadControl::Running)),
+            subscriptions: Arc::new(Mutex::new(Subscriptions::default())),
+            next_subscription_id: Arc::new(Mutex::new(1)),
+            control_token: Arc::new(Mutex::new(None)),
+        };
+
+        let dispatcher = process.clone();
+        tokio::spawn(async move {
+            dispatcher.run_permission_dispatcher(permission_queue_rx).await;
+        });
+
+        if let Some(path) = process.config.control_socket_path.clone() {
+            let token = control::generate_random_token(32);
+            *process.control_token.lock().unwrap() = Some(token.clone());
+            control::spawn(process.clone(), path, token);
         }
+
+        process
+    }
+
+    /// Token a client must present as the first line on the control socket
+    /// before any RPC is dispatched. `None` if `control_socket_path` wasn't
+    /// configured.
+    pub fn control_token(&self) -> Option<String> {
+        self.control_token.lock().unwrap().clone()
+    }
+
+    /// Subscribe to sidecar-originated notifications (JSON-RPC lines with a
+    /// `method` but no `id`), e.g. for the WebSocket bridge to fan out to
+    /// connected frontends.
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<String> {
+        self.notifications.subscribe()
     }
 
     pub fn status(&self) -> SidecarStatus {
@@ -145,26 +733,74 @@ impl SidecarProcess {
             uptime_secs: uptime,
             crash_count,
             started_at,
+            protocol_version: *self.protocol_version.lock().unwrap(),
+            capabilities: self.capabilities.lock().unwrap().clone(),
+            circuit_open: *self.circuit_open.lock().unwrap(),
+            last_error: self.last_error.lock().unwrap().clone(),
+            metrics: self.metrics.lock().unwrap().clone(),
+            last_exit_code: *self.last_exit_code.lock().unwrap(),
+            last_signal: *self.last_signal.lock().unwrap(),
+            last_exit_reason: self.last_exit_reason.lock().unwrap().clone(),
+            last_health_ok: *self.last_health_ok.lock().unwrap(),
+            consecutive_probe_failures: *self.consecutive_probe_failures.lock().unwrap(),
+            remote_addr: match &self.config.spawn_mode {
+                SidecarSpawnMode::Attach { addr } => Some(addr.clone()),
+                SidecarSpawnMode::Spawn => None,
+            },
         }
     }
 
     pub fn state(&self) -> SidecarState {
-        let state = self.state.lock().unwrap();
-        state.clone()
+        self.state.load().as_ref().clone()
     }
 
     pub fn port(&self) -> Option<u16> {
         *self.port.lock().unwrap()
     }
 
-    pub async fn start(&self) -> Result<(), SidecarError> {
-        log::info!("Starting sidecar: {:?}", self.config.binary);
+    /// Accumulated process-lifecycle and per-method RPC dispatch counters.
+    /// Also reachable via [`Self::status`], which embeds the same snapshot,
+    /// but exposed standalone so a metrics exporter doesn't have to pull a
+    /// full status just to scrape counters.
+    pub fn metrics(&self) -> SidecarMetrics {
+        self.metrics.lock().unwrap().clone()
+    }
+
+    /// Token to present to the core's own HTTP endpoints, if configured. Used
+    /// by the host's reverse proxy; unrelated to the host's own admin token.
+    pub fn core_auth_token(&self) -> Option<String> {
+        self.config.core_auth_token.clone()
+    }
 
+    pub async fn start(&self) -> Result<(), SidecarError> {
         {
-            *self.state.lock().unwrap() = SidecarState::Starting;
+            self.state.store(Arc::new(SidecarState::Starting));
             *self.start_time.lock().unwrap() = Some(Instant::now());
+            *self.last_probe_at.lock().unwrap() = None;
+            *self.last_health_ok.lock().unwrap() = true;
+            *self.consecutive_probe_failures.lock().unwrap() = 0;
+        }
+
+        if let SidecarSpawnMode::Attach { addr } = self.config.spawn_mode.clone() {
+            log::info!("Attaching to remote sidecar at {}", addr);
+            return match self.connect_attached(&addr).await {
+                Ok(()) => {
+                    self.state.store(Arc::new(SidecarState::Initializing));
+                    match self.negotiate_protocol().await {
+                        Ok(true) => self.verify_health().await,
+                        Ok(false) => Ok(()),
+                        Err(e) => Err(e),
+                    }
+                }
+                Err(e) => {
+                    self.state.store(Arc::new(SidecarState::Stopped));
+                    Err(e)
+                }
+            };
         }
 
+        log::info!("Starting sidecar: {:?}", self.config.binary);
+
         match self.spawn_sidecar().await {
             Ok(child) => {
                 *self.child.lock().unwrap() = Some(child);
@@ -177,24 +813,31 @@ impl SidecarProcess {
 
                 match port_result {
                     Ok(Ok(port)) => {
-                        *self.state.lock().unwrap() = SidecarState::Running;
                         log::info!("Sidecar started successfully on port {}", port);
-                        Ok(())
+                        self.state.store(Arc::new(SidecarState::Initializing));
+                        match self.negotiate_protocol().await {
+                            // Compatible: don't report `Running` until the
+                            // core actually answers a health check, not just
+                            // because it bound a port.
+                            Ok(true) => self.verify_health().await,
+                            Ok(false) => Ok(()),
+                            Err(e) => Err(e),
+                        }
                     }
                     Ok(Err(e)) => {
-                        *self.state.lock().unwrap() = SidecarState::Running;
+                        self.state.store(Arc::new(SidecarState::Running));
                         log::warn!("Sidecar started but port discovery failed: {:?}", e);
                         Ok(())
                     }
                     Err(_) => {
-                        *self.state.lock().unwrap() = SidecarState::Running;
+                        self.state.store(Arc::new(SidecarState::Running));
                         log::warn!("Sidecar started but port discovery timed out");
                         Ok(())
                     }
                 }
             }
             Err(e) => {
-                *self.state.lock().unwrap() = SidecarState::Stopped;
+                self.state.store(Arc::new(SidecarState::Stopped));
                 Err(e)
             }
         }
@@ -202,53 +845,151 @@ impl SidecarProcess {
 
     pub async fn stop(&self) -> Result<(), SidecarError> {
         log::info!("Stopping sidecar");
-        *self.state.lock().unwrap() = SidecarState::Stopping;
+        self.state.store(Arc::new(SidecarState::Stopping));
+
+        // A deliberate stop, not a crash: disarm the current run's metrics
+        // guard so it counts as a clean exit once it drops.
+        if let Some(guard) = self.metrics_guard.lock().unwrap().as_mut() {
+            guard.disarm();
+        }
+
+        // Tear down any children launched via `process.spawn` first, rather
+        // than orphaning them when the sidecar itself goes away.
+        let spawned: Vec<SpawnedProcess> =
+            self.spawned_processes.lock().unwrap().drain().map(|(_, p)| p).collect();
+        for process in spawned {
+            Self::terminate_spawned_process(process).await;
+        }
+
+        self.shutdown_core().await;
 
         let child_opt = { self.child.lock().unwrap().take() };
 
-        if let Some(mut child) = child_opt {
-            let pid = child.id().unwrap_or_default() as i32;
-            log::info!("Sending SIGTERM to sidecar (PID: {:?})", pid);
+        if let Some(child) = child_opt {
+            Self::terminate_child(child).await?;
+        } else if matches!(self.config.spawn_mode, SidecarSpawnMode::Attach { .. }) {
+            // Nothing to SIGTERM; just drop the TCP connection so `monitor()`
+            // doesn't try to reconnect once we've stored `Stopped` below.
+            *self.transport.lock().await = None;
+        }
 
-            #[cfg(unix)]
-            unsafe {
-                let _ = libc::killpg(pid, libc::SIGTERM);
-            }
-            #[cfg(not(unix))]
-            {
-                let _ = child.start_kill();
-            }
+        self.state.store(Arc::new(SidecarState::Stopped));
+        *self.start_time.lock().unwrap() = None;
+        *self.port.lock().unwrap() = None;
+        *self.last_probe_at.lock().unwrap() = None;
+        *self.last_health_ok.lock().unwrap() = true;
+        *self.consecutive_probe_failures.lock().unwrap() = 0;
 
-            let wait_res = tokio::time::timeout(Duration::from_secs(2), child.wait()).await;
-            match wait_res {
-                Ok(Ok(_)) => {}
-                Ok(Err(e)) => return Err(SidecarError::Io(e)),
-                Err(_) => {
-                    #[cfg(unix)]
-                    unsafe {
-                        let _ = libc::killpg(pid, libc::SIGKILL);
-                    }
-                    #[cfg(not(unix))]
-                    {
-                        let _ = child.start_kill();
-                    }
-                    let _ = child.wait().await;
+        Ok(())
+    }
+
+    /// Ask a core that actually completed the `initialize`/`initialized`
+    /// handshake to wind itself down before resorting to signals, mirroring
+    /// LSP's `shutdown` request (core stops accepting new work but may still
+    /// answer in-flight ones) followed by the `exit` notification (core
+    /// should now terminate its own process). Best-effort: a core that never
+    /// negotiated a protocol version has nothing to ask, and a core that
+    /// doesn't answer within the timeout just falls through to
+    /// [`Self::terminate_child`] as before.
+    async fn shutdown_core(&self) {
+        if self.protocol_version.lock().unwrap().is_none() {
+            return;
+        }
+
+        let shutdown_result = tokio::time::timeout(
+            Duration::from_secs(2),
+            self.call_rpc("shutdown", serde_json::json!({})),
+        )
+        .await;
+
+        match shutdown_result {
+            Ok(Ok(_)) => {
+                if let Err(e) = self.send_notification("exit", serde_json::json!({})).await {
+                    log::warn!("Failed to send 'exit' notification: {:?}", e);
                 }
             }
+            Ok(Err(e)) => {
+                log::warn!("Sidecar rejected 'shutdown' request: {:?}", e);
+            }
+            Err(_) => {
+                log::warn!("Sidecar did not answer 'shutdown' request in time");
+            }
         }
+    }
 
-        *self.state.lock().unwrap() = SidecarState::Stopped;
-        *self.start_time.lock().unwrap() = None;
-        *self.port.lock().unwrap() = None;
+    /// Send SIGTERM, give the child 2s to exit, then escalate to SIGKILL.
+    /// Shared by [`Self::stop`] and the health-probe-failure path in
+    /// [`Self::monitor`], both of which need to stop an unresponsive child
+    /// via the same graceful-then-forceful sequence.
+    async fn terminate_child(mut child: Box<dyn ChildHandle>) -> Result<(), SidecarError> {
+        log::info!("Sending SIGTERM to sidecar (PID: {:?})", child.id());
+
+        #[cfg(unix)]
+        let _ = child.killpg(libc::SIGTERM);
+        #[cfg(not(unix))]
+        let _ = child.kill();
+
+        let wait_res = tokio::time::timeout(Duration::from_secs(2), child.wait()).await;
+        match wait_res {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => return Err(SidecarError::Io(e)),
+            Err(_) => {
+                #[cfg(unix)]
+                let _ = child.killpg(libc::SIGKILL);
+                #[cfg(not(unix))]
+                let _ = child.kill();
+
+                let _ = child.wait().await;
+            }
+        }
 
         Ok(())
     }
 
-    pub async fn monitor(&self) {
-        let mut crash_count = 0;
+    /// Terminate a single `process.spawn`-launched child using the same
+    /// SIGTERM→SIGKILL sequence [`Self::stop`] uses for the sidecar itself,
+    /// rather than `portable_pty::Child::kill`'s unconditional hard kill.
+    async fn terminate_spawned_process(mut process: SpawnedProcess) {
+        let Some(pid) = process.child.process_id().map(|p| p as i32) else {
+            let _ = process.child.kill();
+            return;
+        };
+
+        #[cfg(unix)]
+        unsafe {
+            let _ = libc::kill(pid, libc::SIGTERM);
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = process.child.kill();
+        }
+
+        let waited = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                match process.child.try_wait() {
+                    Ok(Some(_)) | Err(_) => break,
+                    Ok(None) => tokio::time::sleep(Duration::from_millis(50)).await,
+                }
+            }
+        })
+        .await;
+
+        if waited.is_err() {
+            #[cfg(unix)]
+            unsafe {
+                let _ = libc::kill(pid, libc::SIGKILL);
+            }
+            #[cfg(not(unix))]
+            {
+                let _ = process.child.kill();
+            }
+            let _ = process.child.wait();
+        }
+    }
 
+    pub async fn monitor(&self) {
         loop {
-            let state = { self.state.lock().unwrap().clone() };
+            let state = self.state.load().as_ref().clone();
 
             match state {
                 SidecarState::Stopped => {
@@ -257,19 +998,55 @@ impl SidecarProcess {
                 SidecarState::Stopping => {
                     tokio::time::sleep(Duration::from_millis(100)).await;
                 }
-                SidecarState::Running | SidecarState::Starting => {
+                SidecarState::Running | SidecarState::Starting | SidecarState::Initializing => {
+                    let attach_mode = matches!(self.config.spawn_mode, SidecarSpawnMode::Attach { .. });
                     let mut child_dead = false;
-                    {
+                    let mut exit_reason = None;
+
+                    if attach_mode {
+                        // No local child to poll with `try_wait`; liveness is
+                        // judged entirely by the health probe, which for an
+                        // attached core doubles as this mode's crash
+                        // detection (see the `child_dead = true` below).
+                        if self.should_probe() {
+                            self.run_health_probe().await;
+                            if self.probe_failures_exceeded() {
+                                log::warn!(
+                                    "Attached sidecar failed {} consecutive health.ping probes; reconnecting",
+                                    self.config.health_probe_failure_threshold
+                                );
+                                *self.last_exit_code.lock().unwrap() = None;
+                                *self.last_signal.lock().unwrap() = None;
+                                *self.last_exit_reason.lock().unwrap() =
+                                    Some(SidecarExitReason::Unresponsive);
+                                *self.transport.lock().await = None;
+                                child_dead = true;
+                            }
+                        }
+                    } else {
                         let mut child_guard = self.child.lock().unwrap();
                         if let Some(child) = child_guard.as_mut() {
                             match child.try_wait() {
                                 Ok(Some(status)) => {
-                                    log::warn!("Sidecar exited: {:?}", status);
+                                    let reason = SidecarExitReason::classify(status);
+                                    log::warn!("Sidecar exited: {:?} ({:?})", status, reason);
+                                    *self.last_error.lock().unwrap() =
+                                        Some(format!("sidecar exited: {:?}", status));
+                                    *self.last_exit_code.lock().unwrap() = status.code();
+                                    #[cfg(unix)]
+                                    {
+                                        use std::os::unix::process::ExitStatusExt;
+                                        *self.last_signal.lock().unwrap() = status.signal();
+                                    }
+                                    *self.last_exit_reason.lock().unwrap() = Some(reason);
+                                    exit_reason = Some(reason);
                                     child_dead = true;
                                 }
                                 Ok(None) => {}
                                 Err(e) => {
                                     log::error!("Error waiting on child: {:?}", e);
+                                    *self.last_error.lock().unwrap() =
+                                        Some(format!("error waiting on child: {}", e));
                                     child_dead = true;
                                 }
                             }
@@ -284,26 +1061,83 @@ impl SidecarProcess {
                             *child_guard = None;
                         }
 
-                        crash_count += 1;
+                        // A clean code-0 exit or a shutdown via the same
+                        // signals `stop()` sends isn't a crash: don't
+                        // restart, just report it as stopped.
+                        if exit_reason.is_some_and(|r| !r.is_crash()) {
+                            log::info!(
+                                "Sidecar exited without crashing ({:?}); not restarting",
+                                exit_reason
+                            );
+                            self.state.store(Arc::new(SidecarState::Stopped));
+                            return;
+                        }
+
+                        let crash_count = {
+                            let mut count = self.crash_count.lock().unwrap();
+                            *count += 1;
+                            *count
+                        };
+
                         if self.config.max_restarts > 0 && crash_count > self.config.max_restarts {
                             log::error!("Max restarts ({}) exceeded", self.config.max_restarts);
-                            *self.state.lock().unwrap() = SidecarState::Crashed {
+                            self.state.store(Arc::new(SidecarState::Crashed {
                                 attempts: crash_count,
-                            };
+                            }));
+                            return;
+                        }
+
+                        if self.trip_circuit_breaker_if_due(crash_count) {
                             return;
                         }
 
-                        let backoff = calculate_backoff(crash_count, &self.config);
+                        let backoff = self.calculate_backoff(crash_count);
                         log::info!("Restarting in {}ms (Attempt {})", backoff, crash_count);
 
-                        *self.state.lock().unwrap() = SidecarState::Restarting {
+                        self.state.store(Arc::new(SidecarState::Restarting {
                             backoff_ms: backoff,
-                        };
+                        }));
+                        self.metrics.lock().unwrap().restart_backoff_events += 1;
                         tokio::time::sleep(Duration::from_millis(backoff)).await;
 
                         if let Err(e) = self.start().await {
                             log::error!("Failed to restart sidecar: {:?}", e);
+                            *self.last_error.lock().unwrap() = Some(e.to_string());
+                        }
+                    } else if !attach_mode {
+                        // Attach mode already ran its own probe-and-decide
+                        // above, since it has no local child whose death the
+                        // next tick's `try_wait` could observe.
+                        if self.should_probe() {
+                            self.run_health_probe().await;
+
+                            if self.probe_failures_exceeded() {
+                                log::warn!(
+                                    "Sidecar failed {} consecutive health.ping probes; treating as crashed",
+                                    self.config.health_probe_failure_threshold
+                                );
+                                *self.last_exit_code.lock().unwrap() = None;
+                                *self.last_signal.lock().unwrap() = None;
+                                *self.last_exit_reason.lock().unwrap() =
+                                    Some(SidecarExitReason::Unresponsive);
+
+                                let child_opt = { self.child.lock().unwrap().take() };
+                                if let Some(child) = child_opt {
+                                    if let Err(e) = Self::terminate_child(child).await {
+                                        log::error!(
+                                            "Error terminating unresponsive sidecar: {:?}",
+                                            e
+                                        );
+                                    }
+                                }
+                                // Don't sleep here: let the next tick's
+                                // `try_wait` observe the now-dead child and
+                                // run the normal crash/backoff/circuit
+                                // breaker path above.
+                                continue;
+                            }
                         }
+                        tokio::time::sleep(Duration::from_secs(1)).await;
                     } else {
                         tokio::time::sleep(Duration::from_secs(1)).await;
                     }
@@ -314,68 +1148,284 @@ impl SidecarProcess {
                 SidecarState::Crashed { .. } => {
                     tokio::time::sleep(Duration::from_secs(1)).await;
                 }
+                SidecarState::Incompatible { .. } => {
+                    // Not restart-looping on a version mismatch: the same
+                    // mismatched binary would just fail the handshake again.
+                    return;
+                }
             }
         }
     }
 
-    pub async fn shutdown(&self) {
-        let _ = self.stop().await;
+    /// Record a restart in the sliding window and trip the breaker if
+    /// `circuit_breaker_threshold` restarts have landed within
+    /// `circuit_breaker_window_secs`. Tripping halts auto-restart until
+    /// [`SidecarProcess::reset_circuit_breaker`] is called explicitly.
+    fn trip_circuit_breaker_if_due(&self, crash_count: u32) -> bool {
+        let now = Instant::now();
+        let window = Duration::from_secs(self.config.circuit_breaker_window_secs);
+
+        let in_window = {
+            let mut times = self.restart_times.lock().unwrap();
+            times.push_back(now);
+            while times
+                .front()
+                .is_some_and(|t| now.duration_since(*t) > window)
+            {
+                times.pop_front();
+            }
+            times.len() as u32
+        };
+
+        if in_window < self.config.circuit_breaker_threshold {
+            return false;
+        }
+
+        let reason = format!(
+            "circuit breaker tripped: {} restarts within {}s",
+            in_window, self.config.circuit_breaker_window_secs
+        );
+        log::error!("{}", reason);
+        *self.circuit_open.lock().unwrap() = true;
+        *self.last_error.lock().unwrap() = Some(reason);
+        self.state.store(Arc::new(SidecarState::Crashed {
+            attempts: crash_count,
+        }));
+        true
     }
 
-    async fn spawn_sidecar(&self) -> Result<Child, SidecarError> {
-        let binary_path = &self.config.binary;
+    /// Clear a tripped circuit breaker and its restart history so the
+    /// supervisor can resume auto-restart. Does not itself restart the
+    /// process or re-enter `monitor()`; callers (typically
+    /// [`manager::SidecarManager::reset`]) are expected to call `start()` and
+    /// `monitor()` again afterward.
+    pub fn reset_circuit_breaker(&self) {
+        self.restart_times.lock().unwrap().clear();
+        *self.circuit_open.lock().unwrap() = false;
+        *self.last_error.lock().unwrap() = None;
+        *self.crash_count.lock().unwrap() = 0;
+        self.reset_backoff();
+        self.state.store(Arc::new(SidecarState::Stopped));
+    }
 
-        let mut cmd = Command::new(binary_path);
-        cmd.args(&self.config.args);
-        cmd.current_dir(&self.config.cwd);
-        // Set Envs
-        cmd.env("PRYX_LISTEN_ADDR", "127.0.0.1:0");
-        cmd.env("PRYX_DB_PATH", self.config.db_path.to_string_lossy().to_string());
-        cmd.env("PRYX_HOST_RPC", "1");
-        for (k, v) in &self.config.env {
-            cmd.env(k, v);
+    /// Compute the next restart delay under `self.config.backoff_strategy`,
+    /// threading `backoff_prev_ms` through [`decorrelated_jitter_backoff`]
+    /// so each delay is drawn relative to the last one rather than a fixed
+    /// ladder rung.
+    fn calculate_backoff(&self, attempt: u32) -> u64 {
+        match self.config.backoff_strategy {
+            BackoffStrategy::FullJitter => full_jitter_backoff(attempt, &self.config),
+            BackoffStrategy::DecorrelatedJitter => {
+                let mut prev = self.backoff_prev_ms.lock().unwrap();
+                let next = decorrelated_jitter_backoff(*prev, &self.config);
+                *prev = next;
+                next
+            }
         }
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
-        cmd.stdin(Stdio::piped());
+    }
 
-        #[cfg(unix)]
+    /// Re-seed the decorrelated-jitter cursor to `initial_backoff_ms` so a
+    /// child that later flaps again doesn't restart pinned at `max_backoff_ms`
+    /// from a previous, unrelated crash loop. Called once a restarted
+    /// sidecar reports itself healthy in [`Self::verify_health`], and from
+    /// [`Self::reset_circuit_breaker`].
+    fn reset_backoff(&self) {
+        *self.backoff_prev_ms.lock().unwrap() = self.config.initial_backoff_ms;
+    }
+
+    /// Whether the circuit breaker is currently tripped.
+    pub fn circuit_open(&self) -> bool {
+        *self.circuit_open.lock().unwrap()
+    }
+
+    /// Whether `health_probe_interval_secs` has elapsed since the last
+    /// liveness probe, and if so, stakes the claim by recording `now` before
+    /// the caller actually probes (so a slow probe doesn't cause the next
+    /// tick to fire another one immediately). `health_probe_interval_secs ==
+    /// 0` disables active probing entirely.
+    fn should_probe(&self) -> bool {
+        if self.config.health_probe_interval_secs == 0 {
+            return false;
+        }
+
+        let interval = Duration::from_secs(self.config.health_probe_interval_secs);
+        let now = Instant::now();
+        let mut last_probe_at = self.last_probe_at.lock().unwrap();
+        let due = match *last_probe_at {
+            Some(t) => now.duration_since(t) >= interval,
+            None => true,
+        };
+        if due {
+            *last_probe_at = Some(now);
+        }
+        due
+    }
+
+    /// Issue one `health.ping` probe against the running sidecar, bounded by
+    /// `health_probe_timeout_ms`, and update `last_health_ok`/
+    /// `consecutive_probe_failures` accordingly.
+    async fn run_health_probe(&self) {
+        let timeout = Duration::from_millis(self.config.health_probe_timeout_ms);
+        match self
+            .call_rpc_with_timeout("health.ping", serde_json::json!({}), timeout)
+            .await
         {
-            unsafe {
-                cmd.pre_exec(|| {
-                    if libc::setpgid(0, 0) != 0 {
-                        return Err(std::io::Error::last_os_error());
-                    }
-                    Ok(())
-                });
+            Ok(_) => {
+                *self.last_health_ok.lock().unwrap() = true;
+                *self.consecutive_probe_failures.lock().unwrap() = 0;
+            }
+            Err(e) => self.record_probe_failure(format!("health.ping failed: {}", e)),
+        }
+    }
+
+    fn record_probe_failure(&self, reason: String) {
+        log::warn!("{}", reason);
+        *self.last_health_ok.lock().unwrap() = false;
+        *self.last_error.lock().unwrap() = Some(reason);
+        *self.consecutive_probe_failures.lock().unwrap() += 1;
+    }
+
+    /// Whether `consecutive_probe_failures` has reached
+    /// `health_probe_failure_threshold`. A threshold of `0` disables the
+    /// kill-on-unhealthy path even if `should_probe` keeps probing.
+    fn probe_failures_exceeded(&self) -> bool {
+        self.config.health_probe_failure_threshold > 0
+            && *self.consecutive_probe_failures.lock().unwrap()
+                >= self.config.health_probe_failure_threshold
+    }
+
+    pub async fn shutdown(&self) {
+        let _ = self.stop().await;
+    }
+
+    async fn spawn_sidecar(&self) -> Result<Box<dyn ChildHandle>, SidecarError> {
+        let binary_path = &self.config.binary;
+
+        // For the framed transport, bind the RPC socket before spawning so
+        // its path can be handed to the child via env var; the sidecar
+        // connects in once it's up.
+        let rpc_socket_path = match self.config.rpc_transport {
+            RpcTransportKind::Framed => {
+                let suffix: u64 = rand::thread_rng().gen();
+                Some(std::env::temp_dir().join(format!("pryx-rpc-{:x}.sock", suffix)))
             }
+            RpcTransportKind::Stdio | RpcTransportKind::Ipc => None,
+        };
+
+        let mut env = self.config.env.clone();
+        env.insert("PRYX_LISTEN_ADDR".to_string(), "127.0.0.1:0".to_string());
+        env.insert(
+            "PRYX_DB_PATH".to_string(),
+            self.config.db_path.to_string_lossy().to_string(),
+        );
+        env.insert("PRYX_HOST_RPC".to_string(), "1".to_string());
+        if let Some(path) = &rpc_socket_path {
+            env.insert("PRYX_RPC_SOCKET".to_string(), path.to_string_lossy().to_string());
         }
 
-        let mut child = cmd.spawn().map_err(|e| SidecarError::SpawnFailed {
+        let spec = ProcessSpec {
+            binary: binary_path.clone(),
+            args: self.config.args.clone(),
+            env,
+            cwd: self.config.cwd.clone(),
+        };
+
+        let mut child = self.spawner.spawn(&spec).map_err(|e| SidecarError::SpawnFailed {
             binary: binary_path.to_string_lossy().to_string(),
             reason: e.to_string(),
         })?;
 
         log::info!("Spawned sidecar process (PID: {:?})", child.id());
 
+        {
+            let mut metrics = self.metrics.lock().unwrap();
+            metrics.starts += 1;
+        }
+        // Replacing the previous guard (if any) drops it here, recording
+        // that run's duration against whatever arming state `stop()` left it
+        // in: disarmed (clean exit) or still armed (crash).
+        *self.metrics_guard.lock().unwrap() = Some(MetricsGuard::new(
+            binary_path.to_string_lossy().to_string(),
+            self.metrics.clone(),
+        ));
+
         // Capture stdin
-        if let Some(stdin) = child.stdin.take() {
+        if let Some(stdin) = child.take_stdin() {
             *self.stdin.lock().await = Some(stdin);
         }
 
-        // Capture stdout for port discovery AND RPC
-        if let Some(stdout) = child.stdout.take() {
+        // stdout always carries port discovery and diagnostic logging; for
+        // the stdio transport it also carries RPC, multiplexed by a leading
+        // '{'. The framed transport keeps RPC off stdout entirely, so
+        // anything printed there is just logged.
+        let (stdio_frame_tx, stdio_frame_rx) = match self.config.rpc_transport {
+            RpcTransportKind::Stdio => {
+                let (tx, rx) = mpsc::channel(64);
+                (Some(tx), Some(rx))
+            }
+            RpcTransportKind::Framed | RpcTransportKind::Ipc => (None, None),
+        };
+
+        // For the IPC transport, the core picks and binds its own socket
+        // path rather than the host pre-allocating one, so the host learns
+        // it off a `PRYX_CORE_IPC_PATH=` stdout line the same way it learns
+        // a TCP port off `PRYX_CORE_LISTEN_ADDR=`.
+        let (ipc_path_tx, ipc_path_rx) = match self.config.rpc_transport {
+            RpcTransportKind::Ipc => {
+                let (tx, rx) = oneshot::channel();
+                (Some(tx), Some(rx))
+            }
+            RpcTransportKind::Stdio | RpcTransportKind::Framed => (None, None),
+        };
+
+        if let Some(stdout) = child.take_stdout() {
             let reader = BufReader::new(stdout);
             let port = self.port.clone();
-            let process_clone = self.clone();
+            let frame_codec = self.config.frame_codec;
+            let mut ipc_path_tx = ipc_path_tx;
 
             tokio::spawn(async move {
                 let mut lines = reader.lines();
                 while let Ok(Some(line)) = lines.next_line().await {
-                    // 1. Try generic log
+                    // RPC frames are forwarded whole, not logged, when the
+                    // stdio transport is active.
+                    if let Some(tx) = &stdio_frame_tx {
+                        match frame_codec {
+                            FrameCodec::LineDelimited => {
+                                if line.trim().starts_with('{') {
+                                    if tx.send(Frame::rpc(line.into_bytes())).await.is_err() {
+                                        break;
+                                    }
+                                    continue;
+                                }
+                            }
+                            FrameCodec::ContentLength => {
+                                if let Some(len) = parse_content_length(&line) {
+                                    // Consume the remaining (possibly empty)
+                                    // header lines up to the blank line that
+                                    // terminates the LSP-style header block.
+                                    loop {
+                                        match lines.next_line().await {
+                                            Ok(Some(header)) if header.is_empty() => break,
+                                            Ok(Some(_)) => continue,
+                                            _ => return,
+                                        }
+                                    }
+                                    let mut payload = vec![0u8; len];
+                                    if lines.get_mut().read_exact(&mut payload).await.is_err() {
+                                        break;
+                                    }
+                                    if tx.send(Frame::rpc(payload)).await.is_err() {
+                                        break;
+                                    }
+                                    continue;
+                                }
+                            }
+                        }
+                    }
+
                     log::info!("[SIDECAR] {}", line);
 
-                    // 2. Check for port
                     if extract_port_check(&line) {
                         if let Some(p) = extract_port_from_line(&line) {
                             if let Ok(mut port_guard) = port.lock() {
@@ -387,19 +1437,17 @@ impl SidecarProcess {
                         }
                     }
 
-                    // 3. Check for RPC
-                    if line.trim().starts_with('{') {
-                         if let Ok(req) = serde_json::from_str::<RpcRequest>(&line) {
-                             log::info!("Received RPC Request: {:?}", req);
-                             let _ = process_clone.handle_rpc(req).await;
-                         }
+                    if let Some(path) = extract_ipc_path_from_line(&line) {
+                        if let Some(tx) = ipc_path_tx.take() {
+                            let _ = tx.send(path);
+                        }
                     }
                 }
             });
         }
 
         // Stderr logging
-        if let Some(stderr) = child.stderr.take() {
+        if let Some(stderr) = child.take_stderr() {
             let reader = BufReader::new(stderr);
             tokio::spawn(async move {
                 let mut lines = reader.lines();
@@ -409,93 +1457,417 @@ impl SidecarProcess {
             });
         }
 
+        // Bring up the configured transport and hand RPC frames off to the
+        // dispatcher loop below, replacing whatever transport (if any) the
+        // previous run left behind.
+        let transport: Arc<dyn Transport> = match self.config.rpc_transport {
+            RpcTransportKind::Stdio => {
+                let rx = stdio_frame_rx.expect("stdio frame channel created above");
+                Arc::new(StdioTransport::new(self.stdin.clone(), rx, self.config.frame_codec))
+            }
+            RpcTransportKind::Framed => {
+                let path = rpc_socket_path.expect("socket path allocated above");
+                let accept = FramedTransport::listen_and_accept(&path);
+                match tokio::time::timeout(self.config.start_timeout, accept).await {
+                    Ok(Ok(t)) => Arc::new(t),
+                    Ok(Err(e)) => return Err(SidecarError::Io(e)),
+                    Err(_) => {
+                        return Err(SidecarError::HandshakeFailed(
+                            "timed out waiting for sidecar to connect to RPC socket".into(),
+                        ))
+                    }
+                }
+            }
+            RpcTransportKind::Ipc => {
+                let rx = ipc_path_rx.expect("ipc path channel created above");
+                let path = match tokio::time::timeout(self.config.start_timeout, rx).await {
+                    Ok(Ok(path)) => path,
+                    Ok(Err(_)) => {
+                        return Err(SidecarError::HandshakeFailed(
+                            "sidecar exited before advertising its RPC IPC path".into(),
+                        ))
+                    }
+                    Err(_) => {
+                        return Err(SidecarError::HandshakeFailed(
+                            "timed out waiting for sidecar to advertise its RPC IPC path".into(),
+                        ))
+                    }
+                };
+                match IpcTransport::connect(&path).await {
+                    Ok(t) => Arc::new(t),
+                    Err(e) => return Err(SidecarError::Io(e)),
+                }
+            }
+        };
+        self.adopt_transport(transport).await;
+
         Ok(child)
     }
 
-    async fn handle_rpc(&self, req: RpcRequest) -> anyhow::Result<()> {
-        if req.method == "permission.request" {
-            let ans = {
-                // Check app handle
-                let app_guard = self.app_handle.lock().unwrap();
-                if let Some(app) = app_guard.as_ref() {
-                    // Parse params
-                    let description = req.params.get("description").and_then(|v: &Value| v.as_str()).unwrap_or("Unknown Action");
-                    let _intent = req.params.get("intent").and_then(|v: &Value| v.as_str()).unwrap_or("Requested by Runtime");
+    /// Install `transport` as the active RPC channel and spawn the
+    /// background task that reads frames off it: responses are matched
+    /// against `pending_requests`/`stream_requests`, and inbound
+    /// requests/notifications are routed through `handle_rpc_with_metrics`.
+    /// Shared by [`Self::spawn_sidecar`] (stdio/local-socket transports) and
+    /// [`Self::connect_attached`] (a TCP transport to a remote core), since
+    /// once a [`Transport`] exists the two scenarios are handled identically.
+    async fn adopt_transport(&self, transport: Arc<dyn Transport>) {
+        *self.transport.lock().await = Some(transport.clone());
+
+        let process_clone = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let frame = match transport.recv().await {
+                    Ok(frame) => frame,
+                    Err(e) => {
+                        log::warn!("RPC transport closed: {}", e);
+                        break;
+                    }
+                };
 
-                    log::info!("Asking permission for: {}", description);
+                let Ok(val) = process_clone.config.wire_codec.decode(&frame.payload) else {
+                    log::warn!("Received frame that failed to decode under the configured wire codec, ignoring");
+                    continue;
+                };
 
-                    app.dialog().message(description)
-                        .title("Permission Request")
-                        .kind(MessageDialogKind::Warning)
-                        .blocking_show()
-                } else {
-                    log::error!("Cannot handle RPC: No AppHandle");
-                    return Ok(());
-                }
-            };
-            
-            // Construct response
-            let result = serde_json::json!({
-                "approved": ans
-            });
-            
-            let resp = RpcResponse {
-                jsonrpc: "2.0".to_string(),
-                result,
-                id: req.id,
+                if let Some(items) = val.as_array() {
+                    process_clone.handle_batch(items.clone()).await;
+                } else if val.get("result").is_some() || val.get("error").is_some() {
+                    if let Some(id) = val.get("id").and_then(|v| v.as_u64()) {
+                        let had_pending = {
+                            let mut pending = process_clone.pending_requests.lock().await;
+                            if let Some(tx) = pending.remove(&id) {
+                                let reply = match val.get("error") {
+                                    Some(error) => Err(error
+                                        .get("message")
+                                        .and_then(|m| m.as_str())
+                                        .map(str::to_string)
+                                        .unwrap_or_else(|| error.to_string())),
+                                    None => Ok(val.get("result").cloned().unwrap_or(Value::Null)),
+                                };
+                                let _ = tx.send(reply);
+                                true
+                            } else {
+                                false
+                            }
+                        };
+
+                        if !had_pending {
+                            // Not a one-shot call: see if it's a frame for an
+                            // open `subscribe` stream.
+                            let mut streams = process_clone.stream_requests.lock().await;
+                            if let Some(tx) = streams.get(&id) {
+                                let result = val.get("result").cloned().unwrap_or(Value::Null);
+                                let is_final = val.get("error").is_some()
+                                    || result
+                                        .get("done")
+                                        .and_then(|v| v.as_bool())
+                                        .unwrap_or(false);
+                                let _ = tx.send(result).await;
+                                if is_final {
+                                    streams.remove(&id);
+                                }
+                            }
+                        }
+                    }
+                } else if val.get("method").is_some() {
+                    if val.get("id").is_some() {
+                        if let Ok(req) = serde_json::from_value::<RpcRequest>(val) {
+                            log::info!("Received RPC Request: {:?}", req);
+                            if req.method == "permission.request" {
+                                // Hand off to the dispatcher so a slow/blocking
+                                // dialog can't stall this loop; overflow is
+                                // auto-denied rather than backing up here.
+                                process_clone.dispatch_permission_request(req).await;
+                            } else {
+                                match process_clone.handle_rpc_with_metrics(req).await {
+                                    Ok(Some(resp)) => {
+                                        let _ = process_clone.send_response(resp).await;
+                                    }
+                                    Ok(None) => {}
+                                    Err(e) => log::error!("RPC handler failed: {}", e),
+                                }
+                            }
+                        }
+                    } else {
+                        // Notification: no id, nothing to reply to. If it
+                        // carries a `params.subscription` the core chose,
+                        // route it to that subscription's stream as well as
+                        // the generic broadcast below.
+                        if let Some(sub_id) =
+                            val.get("params").and_then(|p| p.get("subscription")).and_then(|s| s.as_str())
+                        {
+                            let subs = process_clone.core_subscriptions.lock().await;
+                            if let Some(tx) = subs.get(sub_id) {
+                                let _ = tx.send(val.get("params").cloned().unwrap_or(Value::Null));
+                            }
+                        }
+
+                        // Fan it out to any subscribed WebSocket clients. Re-
+                        // serialize the decoded value to JSON text rather
+                        // than reusing `frame.payload` verbatim, so WS
+                        // consumers keep seeing JSON regardless of which
+                        // `wire_codec` is actually on the wire.
+                        if let Ok(line) = serde_json::to_string(&val) {
+                            let _ = process_clone.notifications.send(line);
+                        }
+                    }
+                }
+            }
+
+            // The transport is gone (crash, deliberate stop, or a restart
+            // tearing down the old connection): fail every in-flight
+            // `call_rpc`/`subscribe` immediately instead of leaving them to
+            // sit until their own timeout fires.
+            let stale: Vec<_> = process_clone.pending_requests.lock().await.drain().collect();
+            for (_, tx) in stale {
+                let _ = tx.send(Err("sidecar process is not running".into()));
+            }
+            process_clone.stream_requests.lock().await.clear();
+            process_clone.core_subscriptions.lock().await.clear();
+        });
+    }
+
+    /// Connect to a core already listening on `addr` instead of spawning a
+    /// local child, for [`SidecarSpawnMode::Attach`]. Speaks the same
+    /// length-prefixed framed JSON-RPC [`FramedTransport`] uses, just over a
+    /// TCP socket in place of a Unix domain socket / named pipe.
+    async fn connect_attached(&self, addr: &str) -> Result<(), SidecarError> {
+        let connect = tokio::net::TcpStream::connect(addr);
+        let stream = match tokio::time::timeout(self.config.start_timeout, connect).await {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) => return Err(SidecarError::Io(e)),
+            Err(_) => {
+                return Err(SidecarError::HandshakeFailed(format!(
+                    "timed out connecting to attached sidecar at {}",
+                    addr
+                )))
+            }
+        };
+
+        log::info!("Attached to remote sidecar at {}", addr);
+        let transport: Arc<dyn Transport> = Arc::new(TcpTransport::new(stream));
+        self.adopt_transport(transport).await;
+        Ok(())
+    }
+
+    /// Dispatch an inbound `permission.request` onto the bounded permission
+    /// queue so the stdout read loop never blocks on a dialog. If the queue
+    /// is full the request is auto-denied immediately rather than backing up
+    /// the reader.
+    async fn dispatch_permission_request(&self, req: RpcRequest) {
+        if let Err(mpsc::error::TrySendError::Full(req) | mpsc::error::TrySendError::Closed(req)) =
+            self.permission_queue_tx.try_send(req)
+        {
+            log::warn!(
+                "Permission request queue full or closed, auto-denying request id {}",
+                req.id
+            );
+            let resp = RpcResponse::success(
+                req.id,
+                serde_json::json!({
+                    "approved": false,
+                    "outcome": PermissionOutcome::Denied,
+                }),
+            );
+            let _ = self.send_response(resp).await;
+        }
+    }
+
+    /// Drain the permission queue, handling up to `MAX_IN_FLIGHT_PERMISSION_REQUESTS`
+    /// dialogs concurrently so multiple prompts can be outstanding and
+    /// answered out of order. Requests beyond the cap are auto-denied rather
+    /// than queued behind the in-flight ones.
+    async fn run_permission_dispatcher(&self, mut queue: mpsc::Receiver<RpcRequest>) {
+        while let Some(req) = queue.recv().await {
+            match self.permission_in_flight.clone().try_acquire_owned() {
+                Ok(permit) => {
+                    let process = self.clone();
+                    tokio::spawn(async move {
+                        let _ = process.handle_permission_request(req).await;
+                        drop(permit);
+                    });
+                }
+                Err(_) => {
+                    log::warn!(
+                        "Too many in-flight permission requests, auto-denying request id {}",
+                        req.id
+                    );
+                    let resp = RpcResponse::success(
+                        req.id,
+                        serde_json::json!({
+                            "approved": false,
+                            "outcome": PermissionOutcome::Denied,
+                        }),
+                    );
+                    let _ = self.send_response(resp).await;
+                }
+            }
+        }
+    }
+
+    async fn handle_permission_request(&self, req: RpcRequest) -> anyhow::Result<()> {
+        let description = req.params.get("description").and_then(|v: &Value| v.as_str()).unwrap_or("Unknown Action").to_string();
+        let intent = req.params.get("intent").and_then(|v: &Value| v.as_str()).unwrap_or("Requested by Runtime").to_string();
+        let resource = req.params.get("resource").and_then(|v: &Value| v.as_str()).unwrap_or("").to_string();
+        let remember = req.params.get("remember").and_then(|v: &Value| v.as_bool()).unwrap_or(false);
+        let scope = req.params.get("scope").and_then(|v: &Value| v.as_str());
+
+        let cache_key = scope.map(|s| permission_cache_key("permission.request", &intent, &format!("{}:{}", s, resource)));
+
+        let outcome = if let Some(key) = &cache_key {
+            self.permission_decisions.lock().unwrap().get(key).copied()
+        } else {
+            None
+        };
+
+        let outcome = match outcome {
+            Some(cached) => {
+                log::info!("Auto-resolving permission request from cache: {:?}", cached);
+                cached
+            }
+            None => {
+                let app = { self.app_handle.lock().unwrap().clone() };
+                let Some(app) = app else {
+                    log::error!("Cannot handle RPC: No AppHandle");
+                    return Ok(());
+                };
+
+                log::info!("Asking permission for: {}", description);
+
+                let dialog_task = tokio::task::spawn_blocking(move || {
+                    app.dialog()
+                        .message(description)
+                        .title("Permission Request")
+                        .kind(MessageDialogKind::Warning)
+                        .blocking_show()
+                });
+
+                let timeout = Duration::from_millis(self.config.permission_dialog_timeout_ms.max(1));
+                let outcome = match tokio::time::timeout(timeout, dialog_task).await {
+                    Ok(Ok(true)) => PermissionOutcome::Approved,
+                    Ok(Ok(false)) => PermissionOutcome::Denied,
+                    Ok(Err(e)) => {
+                        log::error!("Permission dialog task panicked: {}", e);
+                        PermissionOutcome::Error
+                    }
+                    Err(_) => {
+                        log::warn!("Permission request '{}' timed out, auto-denying", intent);
+                        PermissionOutcome::TimedOut
+                    }
+                };
+
+                if remember {
+                    if let Some(key) = cache_key {
+                        self.permission_decisions.lock().unwrap().insert(key, outcome);
+                    }
+                }
+
+                outcome
+            }
+        };
+
+        // Construct response
+        let result = serde_json::json!({
+            "approved": outcome.approved(),
+            "outcome": outcome,
+        });
+
+        let resp = RpcResponse::success(req.id, result);
+
+        self.send_response(resp).await
+    }
+
+    /// Dispatch `req` through [`Self::handle_rpc`] under a per-method budget
+    /// ([`SidecarConfig::rpc_handler_timeout_ms`]), recording the outcome via
+    /// an [`RpcCallGuard`] so a hung handler shows up in
+    /// [`Self::metrics`] as a `timed_out` dispatch instead of silently
+    /// wedging every other request behind it. Every caller that used to
+    /// invoke `handle_rpc` directly (the stdio/control-channel read loops,
+    /// `handle_batch`) should go through here instead.
+    async fn handle_rpc_with_metrics(&self, req: RpcRequest) -> anyhow::Result<Option<RpcResponse>> {
+        let method = req.method.clone();
+        let req_id = req.id;
+        let mut guard = RpcCallGuard::new(method.clone(), self.metrics.clone());
+        let budget = Duration::from_millis(self.config.rpc_handler_timeout_ms);
+
+        match tokio::time::timeout(budget, self.handle_rpc(req)).await {
+            Ok(result) => {
+                guard.mark_completed();
+                result
+            }
+            Err(_) => {
+                log::warn!("RPC handler for '{}' timed out after {:?}", method, budget);
+                Ok(Some(RpcResponse::error(
+                    req_id,
+                    RpcError::internal(format!("handler for '{}' timed out", method)),
+                )))
+            }
+        }
+    }
+
+    async fn handle_rpc(&self, req: RpcRequest) -> anyhow::Result<Option<RpcResponse>> {
+        let response = if req.method == "permission.clearCache" {
+            let cleared = {
+                let mut cache = self.permission_decisions.lock().unwrap();
+                let n = cache.len();
+                cache.clear();
+                n
             };
-            
-            self.send_response(resp).await?;
+            log::info!("Cleared {} cached permission decisions", cleared);
+
+            Some(RpcResponse::success(req.id, serde_json::json!({ "cleared": cleared })))
         } else if req.method == "notification.show" {
-             let notification_result = {
-                 // Check app handle
-                 let app_guard = self.app_handle.lock().unwrap();
-                 if let Some(app) = app_guard.as_ref() {
+             match req.params.get("body").and_then(|v: &Value| v.as_str()) {
+                 None => Some(RpcResponse::error(req.id, RpcError::invalid_params("missing 'body'"))),
+                 Some(body) => {
                      let title = req.params.get("title").and_then(|v: &Value| v.as_str()).unwrap_or("Pryx Notification");
-                     let body = req.params.get("body").and_then(|v: &Value| v.as_str()).unwrap_or("");
-
-                     log::info!("Showing notification: {} - {}", title, body);
-                     
-                     let _ = app.notification()
-                        .builder()
-                        .title(title)
-                        .body(body)
-                        .show();
-                     
-                     true
-                 } else {
-                     false
-                 }
-             };
+                     let notification_result = {
+                         // Check app handle
+                         let app_guard = self.app_handle.lock().unwrap();
+                         if let Some(app) = app_guard.as_ref() {
+                             log::info!("Showing notification: {} - {}", title, body);
+
+                             let _ = app.notification()
+                                .builder()
+                                .title(title)
+                                .body(body)
+                                .show();
+
+                             true
+                         } else {
+                             false
+                         }
+                     };
 
-             if notification_result {
-                  // Fire and forget response or simple ack
-                 let resp = RpcResponse {
-                    jsonrpc: "2.0".to_string(),
-                    result: serde_json::json!({"status": "ok"}),
-                    id: req.id,
-                };
-                self.send_response(resp).await?;
+                     if notification_result {
+                         // Fire and forget response or simple ack
+                         Some(RpcResponse::success(req.id, serde_json::json!({"status": "ok"})))
+                     } else {
+                         None
+                     }
+                 }
              }
          } else if req.method == "clipboard.writeText" {
-             let text = req.params.get("text").and_then(|v: &Value| v.as_str()).unwrap_or("").to_string();
-             let success = {
-                let app_guard = self.app_handle.lock().unwrap();
-                if let Some(app) = app_guard.as_ref() {
-                    let _ = app.clipboard().write_text(text);
-                    true
-                } else {
-                    false
-                }
-             };
+             match req.params.get("text").and_then(|v: &Value| v.as_str()) {
+                 None => Some(RpcResponse::error(req.id, RpcError::invalid_params("missing 'text'"))),
+                 Some(text) => {
+                     let success = {
+                        let app_guard = self.app_handle.lock().unwrap();
+                        if let Some(app) = app_guard.as_ref() {
+                            let _ = app.clipboard().write_text(text.to_string());
+                            true
+                        } else {
+                            false
+                        }
+                     };
 
-             if success {
-                 let resp = RpcResponse {
-                    jsonrpc: "2.0".to_string(),
-                    result: serde_json::json!({"status": "ok"}),
-                    id: req.id,
-                };
-                self.send_response(resp).await?;
+                     if success {
+                         Some(RpcResponse::success(req.id, serde_json::json!({"status": "ok"})))
+                     } else {
+                         None
+                     }
+                 }
              }
          } else if req.method == "clipboard.readText" {
              let content = {
@@ -506,135 +1878,598 @@ impl SidecarProcess {
                     String::new()
                 }
              };
-             
-             let resp = RpcResponse {
-                jsonrpc: "2.0".to_string(),
-                result: serde_json::json!({"text": content}),
-                id: req.id,
-            };
-            self.send_response(resp).await?;
+
+             Some(RpcResponse::success(req.id, serde_json::json!({"text": content})))
          } else if req.method == "updater.check" {
              let app = self.app_handle.lock().unwrap().clone();
              if let Some(app) = app {
                  log::info!("Checking for updates...");
-                 let updater_res = app.updater();
-                 match updater_res {
+                 match app.updater() {
                      Ok(updater) => {
                          match updater.check().await {
                              Ok(Some(update)) => {
                                  let body = update.body.clone().unwrap_or_default();
                                  let version = update.version.clone();
                                  log::info!("Update found: {} - {}", version, body);
-                                 
-                                 let resp = RpcResponse {
-                                     jsonrpc: "2.0".to_string(),
-                                     result: serde_json::json!({
-                                         "available": true,
-                                         "version": version,
-                                         "body": body
-                                     }),
-                                     id: req.id,
-                                 };
-                                 self.send_response(resp).await?;
+
+                                 Some(RpcResponse::success(req.id, serde_json::json!({
+                                     "available": true,
+                                     "version": version,
+                                     "body": body
+                                 })))
                              }
                              Ok(None) => {
                                  log::info!("No updates available");
-                                 let resp = RpcResponse {
-                                     jsonrpc: "2.0".to_string(),
-                                     result: serde_json::json!({"available": false}),
-                                     id: req.id,
-                                 };
-                                 self.send_response(resp).await?;
+                                 Some(RpcResponse::success(req.id, serde_json::json!({"available": false})))
                              }
                              Err(e) => {
                                  log::error!("Update check failed: {}", e);
-                                 let resp = RpcResponse {
-                                     jsonrpc: "2.0".to_string(),
-                                     result: serde_json::json!({"error": e.to_string()}),
-                                     id: req.id,
-                                 };
-                                 self.send_response(resp).await?;
+                                 Some(RpcResponse::error(req.id, RpcError::internal(e.to_string())))
                              }
                          }
                      }
                      Err(e) => {
                          log::error!("Failed to initialize updater: {}", e);
-                         let resp = RpcResponse {
-                             jsonrpc: "2.0".to_string(),
-                             result: serde_json::json!({"error": e.to_string()}),
-                             id: req.id,
-                         };
-                         self.send_response(resp).await?;
+                         Some(RpcResponse::error(req.id, RpcError::new(RpcError::UPDATER_INIT_FAILED, e.to_string())))
                      }
                  }
+             } else {
+                 None
              }
          } else if req.method == "updater.install" {
              let app = self.app_handle.lock().unwrap().clone();
              if let Some(app) = app {
                  log::info!("Installing update...");
                  // Re-check to get the update object (stateless RPC)
-                 let updater_res = app.updater();
-                 match updater_res {
+                 match app.updater() {
                      Ok(updater) => {
                         match updater.check().await {
                              Ok(Some(update)) => {
-                                 let mut downloaded = 0;
-                                 let mut started = false;
-                                 
-                                 // We can iterate over events if needed, but for now just download and install
-                                 match update.download_and_install(|chunk_length: usize, content_length: Option<u64>| {
-                                     if !started {
-                                         log::info!("Download started. Total: {:?}", content_length);
-                                         started = true;
-                                     }
-                                     downloaded += chunk_length;
-                                     log::debug!("Downloaded {} bytes", downloaded);
-                                 }, || {
-                                     log::info!("Download finished");
-                                 }).await {
-                                     Ok(_) => {
-                                         log::info!("Update installed. Restarting...");
-                                         let resp = RpcResponse {
-                                             jsonrpc: "2.0".to_string(),
-                                             result: serde_json::json!({"status": "installed", "restart": true}),
-                                             id: req.id,
-                                         };
-                                         self.send_response(resp).await?;
-                                         app.restart();
-                                     }
-                                     Err(e) => {
-                                         log::error!("Install failed: {}", e);
-                                          let resp = RpcResponse {
-                                             jsonrpc: "2.0".to_string(),
-                                             result: serde_json::json!({"error": e.to_string()}),
-                                             id: req.id,
-                                         };
-                                         self.send_response(resp).await?;
-                                     }
-                                 }
+                                 // Downloading can take minutes; run it on its
+                                 // own task rather than blocking this read
+                                 // loop from parsing further sidecar frames
+                                 // (same reasoning as `dispatch_permission_request`).
+                                 let process = self.clone();
+                                 let req_id = req.id;
+                                 tokio::spawn(async move {
+                                     process.run_update_install(app, update, req_id).await;
+                                 });
+                                 None
                              }
-                             _ => {
-                                 let resp = RpcResponse {
-                                     jsonrpc: "2.0".to_string(),
-                                     result: serde_json::json!({"error": "No update found to install"}),
-                                     id: req.id,
-                                 };
-                                 self.send_response(resp).await?;
+                             _ => Some(RpcResponse::error(req.id, RpcError::new(RpcError::UPDATE_NOT_FOUND, "No update found to install"))),
+                         }
+                     }
+                     Err(e) => Some(RpcResponse::error(req.id, RpcError::new(RpcError::UPDATER_INIT_FAILED, e.to_string()))),
+                 }
+             } else {
+                 None
+             }
+         } else if req.method == "updater.pause" {
+             *self.update_control.lock().unwrap() = DownloadControl::Paused;
+             Some(RpcResponse::success(req.id, serde_json::json!({"status": "paused"})))
+         } else if req.method == "updater.resume" {
+             *self.update_control.lock().unwrap() = DownloadControl::Running;
+             Some(RpcResponse::success(req.id, serde_json::json!({"status": "resumed"})))
+         } else if req.method == "updater.cancel" {
+             *self.update_control.lock().unwrap() = DownloadControl::Cancelled;
+             Some(RpcResponse::success(req.id, serde_json::json!({"status": "cancelling"})))
+         } else if req.method == "pubsub.subscribe" {
+             let topic = req.params.get("topic").and_then(|v: &Value| v.as_str()).map(str::to_string);
+             match topic {
+                 Some(topic) => {
+                     let sub_id = {
+                         let mut next = self.next_subscription_id.lock().unwrap();
+                         let id = *next;
+                         *next += 1;
+                         id
+                     };
+                     self.subscriptions.lock().unwrap().subscribe(&topic, sub_id);
+                     Some(RpcResponse::success(req.id, serde_json::json!({
+                         "subscription_id": sub_id,
+                         "topic": topic,
+                     })))
+                 }
+                 None => Some(RpcResponse::error(req.id, RpcError::invalid_params("missing 'topic'"))),
+             }
+         } else if req.method == "pubsub.unsubscribe" {
+             let sub_id = req.params.get("subscription_id").and_then(|v: &Value| v.as_u64());
+             match sub_id {
+                 Some(sub_id) => {
+                     let removed = self.subscriptions.lock().unwrap().unsubscribe(sub_id);
+                     Some(RpcResponse::success(req.id, serde_json::json!({"unsubscribed": removed})))
+                 }
+                 None => Some(RpcResponse::error(req.id, RpcError::invalid_params("missing 'subscription_id'"))),
+             }
+         } else if req.method == "sidecar.status" {
+             // Lifecycle query: current state/health without going through
+             // the child process itself, so it works even while the child
+             // is restarting or the circuit breaker is tripped.
+             Some(RpcResponse::success(req.id, serde_json::json!(self.status())))
+         } else if req.method == "call" {
+             // Forward an arbitrary method to the sidecar's own RPC
+             // interface via `call_rpc`, the same path host-initiated calls
+             // use. Lets an out-of-band client (the control channel) drive
+             // the sidecar without the caller needing its own connection.
+             // Refused before the `initialize` handshake has negotiated a
+             // protocol version: calling into a core that hasn't declared
+             // what it supports yet would either hang on a never-registered
+             // method or fail deep inside `call_rpc` instead of here.
+             if self.protocol_version.lock().unwrap().is_none() {
+                 Some(RpcResponse::error(req.id, RpcError::method_not_found("call (handshake not yet negotiated)")))
+             } else {
+                 let target_method = req.params.get("method").and_then(|v: &Value| v.as_str()).map(str::to_string);
+                 let target_params = req.params.get("params").cloned().unwrap_or(Value::Null);
+                 match target_method {
+                     Some(target_method) => match self.call_rpc(&target_method, target_params).await {
+                         Ok(result) => Some(RpcResponse::success(req.id, result)),
+                         Err(e) => Some(RpcResponse::error(req.id, RpcError::internal(e.to_string()))),
+                     },
+                     None => Some(RpcResponse::error(req.id, RpcError::invalid_params("missing 'method'"))),
+                 }
+             }
+         } else if req.method == "process.spawn" {
+             Some(self.handle_process_spawn(req).await?)
+         } else if req.method == "process.write" {
+             let id = req.params.get("id").and_then(|v: &Value| v.as_u64());
+             let data = req.params.get("data").and_then(|v: &Value| v.as_str());
+
+             let resp = match (id, data) {
+                 (Some(id), Some(data)) => {
+                     match base64::engine::general_purpose::STANDARD.decode(data) {
+                         Ok(bytes) => {
+                             let mut processes = self.spawned_processes.lock().unwrap();
+                             match processes.get_mut(&id) {
+                                 Some(process) => match process.writer.write_all(&bytes) {
+                                     Ok(()) => RpcResponse::success(req.id, serde_json::json!({"status": "ok"})),
+                                     Err(e) => RpcResponse::error(req.id, RpcError::internal(e.to_string())),
+                                 },
+                                 None => RpcResponse::error(req.id, RpcError::internal(format!("no spawned process with id {}", id))),
                              }
                          }
+                         Err(e) => RpcResponse::error(req.id, RpcError::invalid_params(format!("invalid base64 'data': {}", e))),
                      }
-                     Err(e) => {
-                         let resp = RpcResponse {
-                             jsonrpc: "2.0".to_string(),
-                             result: serde_json::json!({"error": e.to_string()}),
-                             id: req.id,
-                         };
-                         self.send_response(resp).await?;
+                 }
+                 (None, _) => RpcResponse::error(req.id, RpcError::invalid_params("missing 'id'")),
+                 (Some(_), None) => RpcResponse::error(req.id, RpcError::invalid_params("missing 'data'")),
+             };
+
+             Some(resp)
+         } else if req.method == "process.resize" {
+             let id = req.params.get("id").and_then(|v: &Value| v.as_u64());
+             let rows = req.params.get("rows").and_then(|v: &Value| v.as_u64()).unwrap_or(24) as u16;
+             let cols = req.params.get("cols").and_then(|v: &Value| v.as_u64()).unwrap_or(80) as u16;
+
+             let resp = match id {
+                 Some(id) => {
+                     let processes = self.spawned_processes.lock().unwrap();
+                     match processes.get(&id) {
+                         Some(process) => match process.master.resize(PtySize {
+                             rows,
+                             cols,
+                             pixel_width: 0,
+                             pixel_height: 0,
+                         }) {
+                             Ok(()) => RpcResponse::success(req.id, serde_json::json!({"status": "ok"})),
+                             Err(e) => RpcResponse::error(req.id, RpcError::internal(e.to_string())),
+                         },
+                         None => RpcResponse::error(req.id, RpcError::internal(format!("no spawned process with id {}", id))),
                      }
                  }
-             }
-         }
-        Ok(())
+                 None => RpcResponse::error(req.id, RpcError::invalid_params("missing 'id'")),
+             };
+
+             Some(resp)
+         } else if req.method == "process.close" {
+             let id = req.params.get("id").and_then(|v: &Value| v.as_u64());
+
+             let resp = match id {
+                 Some(id) => {
+                     let process = self.spawned_processes.lock().unwrap().remove(&id);
+                     match process {
+                         Some(mut process) => match process.child.kill() {
+                             Ok(()) => RpcResponse::success(req.id, serde_json::json!({"status": "ok"})),
+                             Err(e) => RpcResponse::error(req.id, RpcError::internal(e.to_string())),
+                         },
+                         None => RpcResponse::error(req.id, RpcError::internal(format!("no spawned process with id {}", id))),
+                     }
+                 }
+                 None => RpcResponse::error(req.id, RpcError::invalid_params("missing 'id'")),
+             };
+
+             Some(resp)
+         } else {
+             Some(RpcResponse::error(req.id, RpcError::method_not_found(&req.method)))
+         };
+
+        Ok(response)
+    }
+
+    /// Process a batch (top-level JSON array) of incoming requests/
+    /// notifications per JSON-RPC 2.0, collecting the responses to
+    /// requests that have an `id` into a single array frame and omitting
+    /// one entirely for notifications, as the spec requires.
+    async fn handle_batch(&self, items: Vec<Value>) {
+        let mut responses: Vec<RpcResponse> = Vec::new();
+
+        for item in items {
+            let id = item.get("id").and_then(|v| v.as_u64());
+            match serde_json::from_value::<RpcRequest>(item.clone()) {
+                Ok(req) if req.method == "permission.request" => {
+                    // Dispatched and answered asynchronously like the
+                    // single-request path; not collected into this batch's
+                    // response array.
+                    self.dispatch_permission_request(req).await;
+                }
+                Ok(req) => match self.handle_rpc_with_metrics(req).await {
+                    Ok(Some(resp)) => responses.push(resp),
+                    Ok(None) => {}
+                    Err(e) => log::error!("Batch RPC handler failed: {}", e),
+                },
+                Err(_) => {
+                    if item.get("method").is_none() {
+                        if let Some(id) = id {
+                            responses.push(RpcResponse::error(id, RpcError::invalid_request("Invalid Request")));
+                        }
+                    } else if id.is_none() {
+                        // Notification: no id, nothing to reply to. Fan it
+                        // out to any subscribed WebSocket clients.
+                        let _ = self.notifications.send(item.to_string());
+                    }
+                }
+            }
+        }
+
+        if responses.is_empty() {
+            return;
+        }
+
+        let payload = match serde_json::to_value(&responses)
+            .map_err(|e| e.to_string())
+            .and_then(|value| self.config.wire_codec.encode(&value).map_err(|e| e.to_string()))
+        {
+            Ok(payload) => payload,
+            Err(e) => {
+                log::error!("Failed to serialize batch RPC response: {}", e);
+                return;
+            }
+        };
+
+        if let Ok(transport) = self.transport().await {
+            let _ = transport.send(Frame::rpc(payload)).await;
+        }
+    }
+
+    /// Where the in-progress download's resumability record lives, next to
+    /// the partial download itself. `None` if `app_config_dir` can't be
+    /// resolved, in which case the download just runs without resume support.
+    fn update_download_paths(app: &AppHandle) -> Option<(PathBuf, PathBuf)> {
+        let dir = app.path().app_config_dir().ok()?;
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            log::warn!("Failed to create app config dir for update download state: {}", e);
+            return None;
+        }
+        Some((
+            dir.join("update_download.json"),
+            dir.join("update_download.part"),
+        ))
+    }
+
+    fn load_update_download_state(state_path: &Path) -> Option<UpdateDownloadState> {
+        let bytes = std::fs::read(state_path).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    fn save_update_download_state(state_path: &Path, state: &UpdateDownloadState) {
+        match serde_json::to_vec(state) {
+            Ok(bytes) => {
+                if let Err(e) = std::fs::write(state_path, bytes) {
+                    log::warn!("Failed to persist update download state: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Failed to serialize update download state: {}", e),
+        }
+    }
+
+    fn clear_update_download_state(state_path: &Path, part_path: &Path) {
+        let _ = std::fs::remove_file(state_path);
+        let _ = std::fs::remove_file(part_path);
+    }
+
+    /// Download `update` over HTTP ourselves (rather than
+    /// `Update::download_and_install`'s all-in-one call) so the download can
+    /// be observed via `updater.progress` notifications, steered by
+    /// `updater.pause`/`updater.resume`/`updater.cancel`, and resumed with a
+    /// Range request if a previous attempt for the same URL left a partial
+    /// download on disk. Always replies to `req_id` exactly once, either with
+    /// the install result or an error, and restarts the app on success.
+    async fn run_update_install(&self, app: AppHandle, update: tauri_plugin_updater::Update, req_id: u64) {
+        *self.update_control.lock().unwrap() = DownloadControl::Running;
+
+        let url = update.download_url.to_string();
+        let paths = Self::update_download_paths(&app);
+
+        let mut bytes_so_far: u64 = 0;
+        if let Some((state_path, part_path)) = &paths {
+            if let Some(state) = Self::load_update_download_state(state_path) {
+                if state.url == url && part_path.exists() {
+                    bytes_so_far = state.bytes_so_far;
+                    log::info!("Resuming update download from byte {}", bytes_so_far);
+                }
+            }
+        }
+
+        let client = reqwest::Client::new();
+        let mut request = client.get(&url);
+        if bytes_so_far > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", bytes_so_far));
+        }
+
+        let response = match request.send().await {
+            Ok(resp) if resp.status().is_success() || resp.status() == reqwest::StatusCode::PARTIAL_CONTENT => resp,
+            Ok(resp) => {
+                let resp = RpcResponse::error(req_id, RpcError::internal(format!("download failed: HTTP {}", resp.status())));
+                let _ = self.send_response(resp).await;
+                return;
+            }
+            Err(e) => {
+                let resp = RpcResponse::error(req_id, RpcError::internal(format!("download request failed: {}", e)));
+                let _ = self.send_response(resp).await;
+                return;
+            }
+        };
+
+        // A server that ignores `Range` sends the whole body back with a
+        // plain 200; don't double-append what's already on disk in that case.
+        let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+        if !resumed {
+            bytes_so_far = 0;
+        }
+        let total_len = response.content_length().map(|len| {
+            if resumed { len + bytes_so_far } else { len }
+        });
+
+        let mut part_file = match &paths {
+            Some((_, part_path)) => {
+                match tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(resumed)
+                    .truncate(!resumed)
+                    .open(part_path)
+                    .await
+                {
+                    Ok(f) => Some(f),
+                    Err(e) => {
+                        log::warn!("Failed to open partial download file: {}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let started_at = Instant::now();
+        let mut stream = response.bytes_stream();
+
+        loop {
+            loop {
+                let control = *self.update_control.lock().unwrap();
+                match control {
+                    DownloadControl::Running => break,
+                    DownloadControl::Paused => {
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                    }
+                    DownloadControl::Cancelled => {
+                        log::info!("Update download cancelled");
+                        if let Some((state_path, part_path)) = &paths {
+                            Self::clear_update_download_state(state_path, part_path);
+                        }
+                        let resp = RpcResponse::error(req_id, RpcError::new(RpcError::UPDATE_CANCELLED, "Update download cancelled"));
+                        let _ = self.send_response(resp).await;
+                        return;
+                    }
+                }
+            }
+
+            let chunk = match stream.next().await {
+                Some(Ok(chunk)) => chunk,
+                Some(Err(e)) => {
+                    let resp = RpcResponse::error(req_id, RpcError::internal(format!("download stream failed: {}", e)));
+                    let _ = self.send_response(resp).await;
+                    return;
+                }
+                None => break,
+            };
+
+            if let Some(file) = part_file.as_mut() {
+                if let Err(e) = file.write_all(&chunk).await {
+                    let resp = RpcResponse::error(req_id, RpcError::internal(format!("failed to write download chunk: {}", e)));
+                    let _ = self.send_response(resp).await;
+                    return;
+                }
+            }
+            bytes_so_far += chunk.len() as u64;
+
+            if let Some((state_path, _)) = &paths {
+                Self::save_update_download_state(
+                    state_path,
+                    &UpdateDownloadState { url: url.clone(), total_len, bytes_so_far },
+                );
+            }
+
+            let bytes_per_sec = bytes_so_far as f64 / started_at.elapsed().as_secs_f64().max(0.001);
+            let percent = total_len.map(|total| {
+                if total > 0 { bytes_so_far as f64 / total as f64 * 100.0 } else { 0.0 }
+            });
+            let _ = self.send_notification("updater.progress", serde_json::json!({
+                "downloaded": bytes_so_far,
+                "total": total_len,
+                "percent": percent,
+                "bytes_per_sec": bytes_per_sec as u64,
+            })).await;
+        }
+
+        if let Some(mut file) = part_file {
+            if let Err(e) = file.flush().await {
+                let resp = RpcResponse::error(req_id, RpcError::internal(format!("failed to flush download: {}", e)));
+                let _ = self.send_response(resp).await;
+                return;
+            }
+        }
+
+        let installed_bytes = match &paths {
+            Some((_, part_path)) => match tokio::fs::read(part_path).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    let resp = RpcResponse::error(req_id, RpcError::internal(format!("failed to read downloaded update: {}", e)));
+                    let _ = self.send_response(resp).await;
+                    return;
+                }
+            },
+            None => {
+                let resp = RpcResponse::error(req_id, RpcError::internal("no app config dir to stage the download in"));
+                let _ = self.send_response(resp).await;
+                return;
+            }
+        };
+
+        match update.install(installed_bytes.as_slice()) {
+            Ok(()) => {
+                log::info!("Update installed. Restarting...");
+                if let Some((state_path, part_path)) = &paths {
+                    Self::clear_update_download_state(state_path, part_path);
+                }
+                let _ = self.send_notification("updater.ready", serde_json::json!({"status": "ready"})).await;
+                // Flush the response before restarting: once `app.restart()`
+                // runs the process is gone, so the caller can't send it for us.
+                let resp = RpcResponse::success(req_id, serde_json::json!({"status": "installed", "restart": true}));
+                let _ = self.send_response(resp).await;
+                app.restart();
+            }
+            Err(e) => {
+                log::error!("Install failed: {}", e);
+                let resp = RpcResponse::error(req_id, RpcError::internal(e.to_string()));
+                let _ = self.send_response(resp).await;
+            }
+        }
+    }
+
+    /// Launch an interactive child command under a PTY on the sidecar's
+    /// behalf, streaming its output back as `process.output` notifications
+    /// and delivering a final `process.exit` once it dies. Modeled on
+    /// [`crate::pty::PtyManager::spawn`], but keyed by a numeric id (matching
+    /// the `pending_requests`/`stream_requests` id schemes) instead of a
+    /// UUID, and notified over the sidecar RPC channel rather than a
+    /// WebSocket broadcast.
+    async fn handle_process_spawn(&self, req: RpcRequest) -> anyhow::Result<RpcResponse> {
+        let command = req
+            .params
+            .get("command")
+            .and_then(|v: &Value| v.as_str())
+            .unwrap_or("")
+            .to_string();
+
+        if command.is_empty() {
+            return Ok(RpcResponse::error(req.id, RpcError::invalid_params("missing 'command'")));
+        }
+
+        let args: Vec<String> = req
+            .params
+            .get("args")
+            .and_then(|v| v.as_array())
+            .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+        let cwd = req.params.get("cwd").and_then(|v: &Value| v.as_str()).map(PathBuf::from);
+        let rows = req.params.get("rows").and_then(|v| v.as_u64()).unwrap_or(24) as u16;
+        let cols = req.params.get("cols").and_then(|v| v.as_u64()).unwrap_or(80) as u16;
+
+        let pty_system = native_pty_system();
+        let pair = match pty_system.openpty(PtySize { rows, cols, pixel_width: 0, pixel_height: 0 }) {
+            Ok(pair) => pair,
+            Err(e) => {
+                return Ok(RpcResponse::error(req.id, RpcError::internal(format!("failed to allocate pty: {}", e))));
+            }
+        };
+
+        let mut builder = CommandBuilder::new(&command);
+        builder.args(&args);
+        if let Some(cwd) = cwd {
+            builder.cwd(cwd);
+        }
+
+        let child = match pair.slave.spawn_command(builder) {
+            Ok(child) => child,
+            Err(e) => {
+                return Ok(RpcResponse::error(req.id, RpcError::internal(format!("failed to spawn '{}': {}", command, e))));
+            }
+        };
+        drop(pair.slave);
+
+        let writer = pair.master.take_writer()?;
+        let reader = pair.master.try_clone_reader()?;
+
+        let process_id = {
+            let mut id_guard = self.next_process_id.lock().unwrap();
+            let id = *id_guard;
+            *id_guard += 1;
+            id
+        };
+
+        self.spawned_processes.lock().unwrap().insert(
+            process_id,
+            SpawnedProcess {
+                master: pair.master,
+                writer,
+                child,
+            },
+        );
+
+        let process_clone = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let handle = tokio::runtime::Handle::current();
+            let mut reader = reader;
+            let mut buf = [0u8; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        let data = base64::engine::general_purpose::STANDARD.encode(&buf[..n]);
+                        let params = serde_json::json!({ "id": process_id, "data": data });
+                        let _ = handle.block_on(process_clone.send_notification("process.output", params));
+                    }
+                    Err(_) => break,
+                }
+            }
+
+            let status = process_clone
+                .spawned_processes
+                .lock()
+                .unwrap()
+                .get_mut(&process_id)
+                .and_then(|p| p.child.wait().ok());
+            process_clone.spawned_processes.lock().unwrap().remove(&process_id);
+
+            let params = match status {
+                Some(status) => serde_json::json!({
+                    "id": process_id,
+                    "success": status.success(),
+                    "code": status.exit_code(),
+                }),
+                None => serde_json::json!({ "id": process_id, "success": false }),
+            };
+            let _ = handle.block_on(process_clone.send_notification("process.exit", params));
+        });
+
+        Ok(RpcResponse::success(req.id, serde_json::json!({"id": process_id})))
+    }
+
+    /// Current RPC transport, if the sidecar has been spawned at least once.
+    async fn transport(&self) -> Result<Arc<dyn Transport>, SidecarError> {
+        self.transport
+            .lock()
+            .await
+            .clone()
+            .ok_or_else(|| SidecarError::ProcessNotRunning("RPC transport not available".into()))
     }
 
     pub async fn send_notification(&self, method: &str, params: serde_json::Value) -> Result<(), SidecarError> {
@@ -644,40 +2479,382 @@ impl SidecarProcess {
              "params": params
          });
 
-         let json_line = serde_json::to_string(&notification).map_err(|e| SidecarError::Serialization(e.to_string()))?;
+         let payload = self.config.wire_codec.encode(&notification).map_err(SidecarError::Io)?;
 
-         let mut stdin_guard = self.stdin.lock().await;
-         if let Some(stdin) = stdin_guard.as_mut() {
-             stdin.write_all(json_line.as_bytes()).await.map_err(SidecarError::Io)?;
-             stdin.write_all(b"\n").await.map_err(SidecarError::Io)?;
-             stdin.flush().await.map_err(SidecarError::Io)?;
-             Ok(())
-         } else {
-             Err(SidecarError::ProcessNotRunning("Stdin not available".into()))
-         }
+         self.transport().await?.send(Frame::rpc(payload)).await.map_err(SidecarError::Io)
     }
 
-    async fn send_response(&self, resp: RpcResponse) -> anyhow::Result<()> {
-        let json = serde_json::to_string(&resp)?;
-        log::info!("Sending RPC Response: {}", json);
-        
-        let mut stdin_guard = self.stdin.lock().await;
-        if let Some(stdin) = stdin_guard.as_mut() {
-            stdin.write_all(json.as_bytes()).await.map_err(|e| anyhow::anyhow!(e))?;
-            stdin.write_all(b"\n").await.map_err(|e| anyhow::anyhow!(e))?;
-            stdin.flush().await.map_err(|e| anyhow::anyhow!(e))?;
+    /// Fan `params` out to every current subscriber of `topic` as a
+    /// `pubsub.event` notification, tagging each delivery with its own
+    /// `subscription_id` so a caller with more than one subscription can
+    /// tell which fired. A no-op if nobody has subscribed to `topic`.
+    pub async fn publish(&self, topic: &str, params: serde_json::Value) -> Result<(), SidecarError> {
+        let sub_ids: Vec<u64> = self
+            .subscriptions
+            .lock()
+            .unwrap()
+            .subscribers(topic)
+            .copied()
+            .collect();
+
+        for sub_id in sub_ids {
+            self.send_notification("pubsub.event", serde_json::json!({
+                "topic": topic,
+                "subscription_id": sub_id,
+                "params": params,
+            }))
+            .await?;
         }
+
+        Ok(())
+    }
+
+    async fn send_response(&self, resp: RpcResponse) -> anyhow::Result<()> {
+        log::info!("Sending RPC Response: {}", serde_json::to_string(&resp)?);
+        let payload = self.config.wire_codec.encode(&serde_json::to_value(&resp)?)?;
+
+        self.transport().await?.send(Frame::rpc(payload)).await?;
         Ok(())
     }
 
+    /// Issue a JSON-RPC request to the sidecar over the active transport and
+    /// await the correlated response.
+    pub async fn call_rpc(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+        self.call_rpc_with_timeout(method, params, Duration::from_secs(10)).await
+    }
+
+    /// Like [`Self::call_rpc`], but with the response timeout as a
+    /// parameter rather than a fixed 10s, so callers like
+    /// [`Self::run_health_probe`] that need a short per-call budget don't
+    /// have to wrap the call in their own outer `tokio::time::timeout` —
+    /// doing so would drop this future (and its `pending_requests` cleanup)
+    /// before it runs, leaking the entry forever.
+    async fn call_rpc_with_timeout(
+        &self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> anyhow::Result<Value> {
+        let id = self.next_rpc_id.fetch_add(1, Ordering::Relaxed);
+
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut pending = self.pending_requests.lock().await;
+            pending.insert(id, tx);
+        }
+
+        let req = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+            "id": id
+        });
+        let payload = self.config.wire_codec.encode(&req)?;
+
+        let transport = self.transport().await?;
+        if let Err(e) = transport.send(Frame::rpc(payload)).await {
+            self.pending_requests.lock().await.remove(&id);
+            return Err(anyhow::anyhow!("Failed to send RPC request: {}", e));
+        }
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Ok(val))) => Ok(val),
+            Ok(Ok(Err(message))) => Err(anyhow::anyhow!("RPC request '{}' failed: {}", method, message)),
+            Ok(Err(_)) => {
+                self.pending_requests.lock().await.remove(&id);
+                Err(anyhow::anyhow!("RPC response channel closed"))
+            }
+            Err(_) => {
+                self.pending_requests.lock().await.remove(&id);
+                Err(anyhow::anyhow!("RPC request '{}' timed out", method))
+            }
+        }
+    }
+
+    /// Issue a streaming JSON-RPC request: unlike [`Self::call_rpc`], the
+    /// sidecar may reply with several `{"id": N, "result": ...}` frames
+    /// sharing this request's id (e.g. incremental tool output or a log
+    /// tail) before a terminating frame whose `result.done` is `true`, or an
+    /// error frame. Each frame's `result` is forwarded on the returned
+    /// stream, which ends once the terminator arrives.
+    pub async fn subscribe(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> anyhow::Result<impl Stream<Item = Value>> {
+        let id = self.next_rpc_id.fetch_add(1, Ordering::Relaxed);
+
+        let (tx, rx) = mpsc::channel(32);
+        {
+            let mut streams = self.stream_requests.lock().await;
+            streams.insert(id, tx);
+        }
+
+        let req = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+            "id": id
+        });
+        let payload = self.config.wire_codec.encode(&req)?;
+
+        let transport = self.transport().await?;
+        if let Err(e) = transport.send(Frame::rpc(payload)).await {
+            self.stream_requests.lock().await.remove(&id);
+            return Err(e.into());
+        }
+
+        Ok(ReceiverStream::new(rx))
+    }
+
+    /// Subscribe to a core-initiated notification stream tagged with
+    /// `subscription_id` (e.g. progress events, log tails, indexing
+    /// updates). Unlike [`Self::subscribe`], which correlates frames by the
+    /// id a host-initiated streaming request allocated, these are
+    /// notifications the core pushes unprompted once some other call (say,
+    /// `indexing.start`) hands back `subscription_id` for the caller to
+    /// listen on. The stdout read loop routes any notification whose
+    /// `params.subscription` matches to the returned receiver.
+    pub async fn core_subscribe(&self, subscription_id: String) -> mpsc::UnboundedReceiver<Value> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.core_subscriptions.lock().await.insert(subscription_id, tx);
+        rx
+    }
+
+    /// Drop interest in `subscription_id`, closing its receiver, and best-
+    /// effort tell the core to stop the stream via `subscription.cancel`.
+    /// The RPC is fire-and-forget: the core may already have ended the
+    /// stream itself (e.g. because the underlying work finished), so a
+    /// failure here isn't surfaced to the caller.
+    pub async fn core_unsubscribe(&self, subscription_id: &str) {
+        self.core_subscriptions.lock().await.remove(subscription_id);
+        let _ = self
+            .call_rpc(
+                "subscription.cancel",
+                serde_json::json!({ "subscription": subscription_id }),
+            )
+            .await;
+    }
+
+    /// Negotiate the protocol version and capability set with the sidecar.
+    /// Must run after port discovery succeeds and before the process is
+    /// considered `Running`. Returns `Ok(true)` if the negotiated ranges
+    /// overlap and the caller should proceed to [`Self::verify_health`];
+    /// `Ok(false)` if the sidecar was marked `Incompatible`, which is
+    /// already a terminal outcome.
+    async fn negotiate_protocol(&self) -> Result<bool, SidecarError> {
+        let params = serde_json::json!({
+            "protocol_range": { "min": HOST_PROTOCOL_RANGE.0, "max": HOST_PROTOCOL_RANGE.1 },
+            "features": HOST_FEATURES,
+            "codec": self.config.wire_codec,
+        });
+
+        let result = self
+            .call_rpc("initialize", params)
+            .await
+            .map_err(|e| SidecarError::HandshakeFailed(e.to_string()))?;
+
+        let core_min = result.get("min").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        let core_max = result.get("max").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        let chosen_version = result.get("version").and_then(|v| v.as_u64()).map(|v| v as u32);
+
+        match chosen_version {
+            Some(version) if version < HOST_PROTOCOL_RANGE.0 || version > HOST_PROTOCOL_RANGE.1 => {
+                log::error!(
+                    "Core negotiated version {} outside host range {:?}",
+                    version,
+                    HOST_PROTOCOL_RANGE
+                );
+                self.state.store(Arc::new(SidecarState::Incompatible {
+                    host_range: HOST_PROTOCOL_RANGE,
+                    core_range: (core_min, core_max),
+                }));
+                Ok(false)
+            }
+            Some(version) => {
+                let capabilities: Vec<String> = result
+                    .get("capabilities")
+                    .and_then(|v| v.as_array())
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|v| v.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                log::info!(
+                    "Negotiated protocol version {} with capabilities {:?}",
+                    version,
+                    capabilities
+                );
+                *self.protocol_version.lock().unwrap() = Some(version);
+                *self.capabilities.lock().unwrap() = capabilities;
+
+                // LSP-style: the core doesn't get to rely on anything in the
+                // negotiated capability set until it's seen `initialized`,
+                // mirroring the `initialize` request / `initialized`
+                // notification split so the core can tell "params it must
+                // answer" apart from "fire-and-forget, we're live now".
+                if let Err(e) = self.send_notification("initialized", serde_json::json!({})).await {
+                    log::warn!("Failed to send 'initialized' notification: {:?}", e);
+                }
+
+                Ok(true)
+            }
+            None => {
+                log::error!(
+                    "Sidecar protocol range ({}, {}) is incompatible with host range {:?}",
+                    core_min,
+                    core_max,
+                    HOST_PROTOCOL_RANGE
+                );
+                self.state.store(Arc::new(SidecarState::Incompatible {
+                    host_range: HOST_PROTOCOL_RANGE,
+                    core_range: (core_min, core_max),
+                }));
+                Ok(false)
+            }
+        }
+    }
+
+    /// Gate `Running` on an `admin.health` RPC actually succeeding, rather
+    /// than on the process having merely bound a port. A core that binds but
+    /// can't serve requests is left unhealthy: the child is killed so
+    /// `monitor()` observes it as dead on its next tick and runs the normal
+    /// crash/backoff/circuit-breaker path instead of being reported healthy.
+    async fn verify_health(&self) -> Result<(), SidecarError> {
+        match self.call_rpc("admin.health", serde_json::json!({})).await {
+            Ok(_) => {
+                self.state.store(Arc::new(SidecarState::Running));
+                self.reset_backoff();
+                Ok(())
+            }
+            Err(e) => {
+                let reason = format!("admin.health check failed: {}", e);
+                log::warn!("{}", reason);
+                *self.last_error.lock().unwrap() = Some(reason.clone());
+                if let Some(mut child) = self.child.lock().unwrap().take() {
+                    let _ = child.kill();
+                }
+                Err(SidecarError::HealthCheckFailed(reason))
+            }
+        }
+    }
+
     async fn wait_for_port(&self) -> Result<u16, SidecarError> {
+        let scrape_deadline =
+            Instant::now() + Duration::from_secs(self.config.port_discovery_timeout_secs);
+
         loop {
             if let Some(port) = *self.port.lock().unwrap() {
                 return Ok(port);
             }
+
+            if Instant::now() >= scrape_deadline {
+                if let Some(pid) = self.child.lock().unwrap().as_ref().and_then(|c| c.id()) {
+                    if let Some(port) = discover_port_via_sockets(pid) {
+                        log::info!("Discovered sidecar port {} via socket table", port);
+                        *self.port.lock().unwrap() = Some(port);
+                        return Ok(port);
+                    }
+                }
+            }
+
             tokio::time::sleep(Duration::from_millis(50)).await;
         }
     }
+
+    /// Enumerate the live sockets connected to the sidecar's bound port,
+    /// resolving each remote endpoint's owning process via `sysinfo`. Useful
+    /// for spotting which local tools are actually talking to the core given
+    /// it runs a single shared-token localhost RPC port.
+    pub fn connected_clients(&self) -> Vec<ConnectedClient> {
+        let Some(port) = self.port() else {
+            return Vec::new();
+        };
+
+        use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, TcpState};
+
+        let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+        let proto_flags = ProtocolFlags::TCP;
+        let Ok(sockets) = get_sockets_info(af_flags, proto_flags) else {
+            return Vec::new();
+        };
+
+        let mut system = sysinfo::System::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut clients = Vec::new();
+
+        for si in sockets {
+            let netstat2::ProtocolSocketInfo::Tcp(tcp) = &si.protocol_socket_info else {
+                continue;
+            };
+            if tcp.local_port != port || tcp.state == TcpState::Listen {
+                continue;
+            }
+
+            for pid in &si.associated_pids {
+                if !seen.insert(*pid) {
+                    continue;
+                }
+
+                system.refresh_processes(
+                    sysinfo::ProcessesToUpdate::Some(&[sysinfo::Pid::from_u32(*pid)]),
+                    true,
+                );
+                let name = system
+                    .process(sysinfo::Pid::from_u32(*pid))
+                    .map(|p| p.name().to_string_lossy().to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                clients.push(ConnectedClient {
+                    pid: *pid,
+                    name,
+                    remote_addr: format!("{}:{}", tcp.remote_addr, tcp.remote_port),
+                    state: format!("{:?}", tcp.state),
+                });
+            }
+        }
+
+        clients
+    }
+}
+
+/// A client process observed connected to the sidecar's RPC port.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectedClient {
+    pub pid: u32,
+    pub name: String,
+    pub remote_addr: String,
+    pub state: String,
+}
+
+/// Fall back to enumerating the OS socket table for a loopback TCP socket in
+/// `Listen` state owned by `pid`, for platforms/cores where log-line scraping
+/// doesn't yield a port. Used once `port_discovery_timeout_secs` has elapsed
+/// without a port showing up in stdout.
+fn discover_port_via_sockets(pid: u32) -> Option<u16> {
+    use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, TcpState};
+
+    let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+    let proto_flags = ProtocolFlags::TCP;
+
+    let sockets = get_sockets_info(af_flags, proto_flags).ok()?;
+
+    sockets
+        .into_iter()
+        .filter(|si| si.associated_pids.contains(&pid))
+        .filter_map(|si| match &si.protocol_socket_info {
+            netstat2::ProtocolSocketInfo::Tcp(tcp) if tcp.state == TcpState::Listen => {
+                if tcp.local_addr.is_loopback() || tcp.local_addr.is_unspecified() {
+                    Some(tcp.local_port)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+        .next()
 }
 
 // Helpers
@@ -710,12 +2887,44 @@ fn extract_port_from_line(line: &str) -> Option<u16> {
     None
 }
 
-fn calculate_backoff(attempt: u32, config: &SidecarConfig) -> u64 {
+fn extract_ipc_path_from_line(line: &str) -> Option<PathBuf> {
+    line.strip_prefix("PRYX_CORE_IPC_PATH=").map(|rest| PathBuf::from(rest.trim()))
+}
+
+/// Hash `(method, intent, resource)` into a stable cache key for remembered
+/// permission decisions.
+fn permission_cache_key(method: &str, intent: &str, resource: &str) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    (method, intent, resource).hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// `random(0, min(max_backoff_ms, initial_backoff_ms *
+/// backoff_multiplier^(attempt-1)))`. Jittering the whole range, rather than
+/// just adding noise around the ladder value, avoids restart storms when many
+/// instances crash at once.
+fn full_jitter_backoff(attempt: u32, config: &SidecarConfig) -> u64 {
     let base = config.initial_backoff_ms as f64;
     let multiplier = config.backoff_multiplier;
     let p = (attempt as i32 - 1).clamp(0, 10);
-    let backoff = base * multiplier.powi(p);
-    backoff as u64
+    let ladder = base * multiplier.powi(p);
+    let cap = ladder.min(config.max_backoff_ms as f64).max(0.0) as u64;
+    if cap == 0 {
+        return 0;
+    }
+    rand::thread_rng().gen_range(0..=cap)
+}
+
+/// `next = min(max_backoff_ms, random(initial_backoff_ms, prev *
+/// backoff_multiplier))`. Unlike [`full_jitter_backoff`], each delay is
+/// drawn relative to the previous one rather than a fixed exponential
+/// ladder, which settles into a steadier retry rate under sustained
+/// crash-looping instead of ping-ponging between the ladder's rungs.
+fn decorrelated_jitter_backoff(prev: u64, config: &SidecarConfig) -> u64 {
+    let base = config.initial_backoff_ms.max(1);
+    let hi = ((prev as f64) * config.backoff_multiplier).max(base as f64) as u64;
+    rand::thread_rng().gen_range(base..=hi).min(config.max_backoff_ms)
 }
 
 pub fn find_pryx_core_binary() -> Option<PathBuf> {
@@ -777,6 +2986,12 @@ pub enum SidecarError {
 
     #[error("Operation cancelled")]
     Cancelled,
+
+    #[error("Protocol handshake with sidecar failed: {0}")]
+    HandshakeFailed(String),
+
+    #[error("Sidecar failed its health check: {0}")]
+    HealthCheckFailed(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -787,4 +3002,159 @@ pub struct SidecarStatus {
     pub uptime_secs: Option<f64>,
     pub crash_count: u32,
     pub started_at: Option<String>,
+    /// Protocol version negotiated with the sidecar during the `initialize`
+    /// handshake, or `None` before the handshake has completed.
+    pub protocol_version: Option<u32>,
+    /// Feature flags the sidecar reported supporting at handshake time.
+    pub capabilities: Vec<String>,
+    /// `true` once the circuit breaker has tripped (too many restarts within
+    /// `circuit_breaker_window_secs`). Auto-restart is halted until
+    /// [`SidecarProcess::reset_circuit_breaker`] is called.
+    pub circuit_open: bool,
+    /// Reason for the most recent crash, health-check failure, or breaker
+    /// trip, if any.
+    pub last_error: Option<String>,
+    /// Reliability counters accumulated across this process's lifetime, one
+    /// step up from the raw `crash_count`.
+    pub metrics: SidecarMetrics,
+    /// Numeric exit code of the last child exit, if it exited rather than
+    /// being signaled.
+    pub last_exit_code: Option<i32>,
+    /// Terminating signal of the last child exit, on Unix, if any.
+    pub last_signal: Option<i32>,
+    /// Classification of the last child exit.
+    pub last_exit_reason: Option<SidecarExitReason>,
+    /// Result of the most recent `health.ping` liveness probe. `true`
+    /// before the first probe has run.
+    pub last_health_ok: bool,
+    /// Consecutive liveness-probe failures; resets to 0 on the next success.
+    pub consecutive_probe_failures: u32,
+    /// `addr` this process is attached to under [`SidecarSpawnMode::Attach`],
+    /// or `None` when running a locally-spawned child (`pid` is populated
+    /// instead).
+    pub remote_addr: Option<String>,
+}
+
+/// Process-lifecycle reliability counters for one [`SidecarProcess`],
+/// accumulated by [`MetricsGuard`] as each run of the child ends.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SidecarMetrics {
+    /// Number of times the child has been spawned.
+    pub starts: u64,
+    /// Runs that ended via a disarmed guard (i.e. a deliberate `stop()`).
+    pub clean_exits: u64,
+    /// Runs that ended with the guard still armed (i.e. the child died or
+    /// failed its health check without `stop()` being called).
+    pub crashes: u64,
+    /// Sum of the durations of every completed run, clean or crashed.
+    pub total_uptime_secs: f64,
+    /// Duration of the most recently completed run, if any.
+    pub last_exit_duration_secs: Option<f64>,
+    /// Number of times `monitor()` slept out a restart backoff before
+    /// respawning the child.
+    pub restart_backoff_events: u64,
+    /// `handle_rpc` dispatch counters, keyed by method name.
+    pub rpc_calls: HashMap<String, RpcMethodMetrics>,
+}
+
+/// Dispatch counters for a single RPC method, accumulated by
+/// [`RpcCallGuard`] as each `handle_rpc` call for that method ends.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RpcMethodMetrics {
+    /// Dispatches that returned before [`SidecarConfig::rpc_handler_timeout_ms`]
+    /// elapsed.
+    pub completed: u64,
+    /// Dispatches that hit the per-method timeout (recorded as `completed:
+    /// false`, per the request that added this metric).
+    pub timed_out: u64,
+    /// Sum of the durations of every dispatch of this method, completed or
+    /// timed out.
+    pub total_duration_secs: f64,
+}
+
+/// Ported from pict-rs's metrics guard pattern: armed on creation, it records
+/// a run's duration and outcome into the shared [`SidecarMetrics`] on `Drop`,
+/// crediting a `clean_exit` if [`Self::disarm`] was called (a deliberate
+/// `stop()`) or a `crash` otherwise. One guard covers one spawn of the child;
+/// `spawn_sidecar` replaces it on every restart.
+#[derive(Debug)]
+struct MetricsGuard {
+    start: Instant,
+    armed: bool,
+    #[allow(dead_code)]
+    command: String,
+    metrics: Arc<Mutex<SidecarMetrics>>,
+}
+
+impl MetricsGuard {
+    fn new(command: String, metrics: Arc<Mutex<SidecarMetrics>>) -> Self {
+        Self {
+            start: Instant::now(),
+            armed: true,
+            command,
+            metrics,
+        }
+    }
+
+    /// Mark this run as a clean exit rather than a crash.
+    fn disarm(&mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for MetricsGuard {
+    fn drop(&mut self) {
+        let duration = self.start.elapsed().as_secs_f64();
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.total_uptime_secs += duration;
+        metrics.last_exit_duration_secs = Some(duration);
+        if self.armed {
+            metrics.crashes += 1;
+        } else {
+            metrics.clean_exits += 1;
+        }
+    }
+}
+
+/// Same pattern as [`MetricsGuard`], scoped to a single `handle_rpc`
+/// dispatch instead of a whole child run: armed on creation, it records the
+/// dispatch's duration into [`SidecarMetrics::rpc_calls`] on `Drop`,
+/// crediting `completed` if [`Self::mark_completed`] was called before the
+/// handler returned, or `timed_out` otherwise (a timeout, panic, or early
+/// drop all look the same from here: the handler never finished).
+#[derive(Debug)]
+struct RpcCallGuard {
+    method: String,
+    start: Instant,
+    completed: bool,
+    metrics: Arc<Mutex<SidecarMetrics>>,
+}
+
+impl RpcCallGuard {
+    fn new(method: String, metrics: Arc<Mutex<SidecarMetrics>>) -> Self {
+        Self {
+            method,
+            start: Instant::now(),
+            completed: false,
+            metrics,
+        }
+    }
+
+    fn mark_completed(&mut self) {
+        self.completed = true;
+    }
+}
+
+impl Drop for RpcCallGuard {
+    fn drop(&mut self) {
+        let duration = self.start.elapsed().as_secs_f64();
+        let mut metrics = self.metrics.lock().unwrap();
+        let entry = metrics.rpc_calls.entry(std::mem::take(&mut self.method)).or_default();
+        entry.total_duration_secs += duration;
+        if self.completed {
+            entry.completed += 1;
+        } else {
+            entry.timed_out += 1;
+        }
+    }
 }
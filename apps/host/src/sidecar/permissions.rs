@@ -24,27 +24,90 @@ pub enum ApprovalResponse {
 	Denied,
 	#[serde(rename = "cancelled")]
 	Cancelled,
+	/// No decision arrived within `dialog_timeout_ms`.
+	#[serde(rename = "timed_out")]
+	TimedOut,
+	/// The dialog itself could not be shown or failed unexpectedly.
+	#[serde(rename = "error")]
+	Error { reason: String },
+}
+
+/// One line of the append-only permission audit log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalAuditEntry {
+	pub request_id: String,
+	pub tool_name: String,
+	/// Hash of the request arguments, so the log doesn't need to retain the
+	/// (possibly sensitive) argument values themselves.
+	pub args_digest: String,
+	pub outcome: ApprovalResponse,
+	pub timestamp_ms: u128,
+}
+
+pub fn digest_args(args: &serde_json::Value) -> String {
+	use std::hash::{Hash, Hasher};
+	let mut hasher = std::collections::hash_map::DefaultHasher::new();
+	args.to_string().hash(&mut hasher);
+	format!("{:016x}", hasher.finish())
+}
+
+/// Append one decision to the audit log at `path`, creating it if needed.
+pub fn record_audit_entry(path: &std::path::Path, entry: &ApprovalAuditEntry) -> std::io::Result<()> {
+	use std::io::Write;
+	let line = serde_json::to_string(entry)?;
+	let mut file = std::fs::OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(path)?;
+	writeln!(file, "{}", line)
 }
 
 /// Permission dialog configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PermissionDialogConfig {
 	pub show_native_dialog: bool,
+	/// How long to wait for a user decision before resolving to
+	/// `ApprovalResponse::TimedOut`.
 	pub dialog_timeout_ms: u64,
 	pub approval_required_for_critical: bool,
 }
 
-/// Permission manager for handling approvals
+impl Default for PermissionDialogConfig {
+	fn default() -> Self {
+		Self {
+			show_native_dialog: false,
+			dialog_timeout_ms: 30_000,
+			approval_required_for_critical: false,
+		}
+	}
+}
+
+/// Permission manager for handling approvals.
+///
+/// One instance is shared (via `app.manage(Arc::new(PermissionManager::new()))`
+/// in `main.rs::main()`) across every `*_approval` Tauri command, since
+/// `pending_requests`/`request_senders` must outlive a single command
+/// invocation: `cancel_approval` has to reach the very `request_senders`
+/// entry a concurrent, still-in-flight `request_tool_approval` call
+/// registered, which a fresh `PermissionManager` per command can never see.
+/// `PermissionDialogConfig` isn't stored here, unlike the state above — it's
+/// passed into [`Self::request_approval`] per call so it keeps tracking
+/// `SettingsStore`'s hot-reloaded value instead of the snapshot in effect
+/// when this manager was constructed.
 pub struct PermissionManager {
-	pub config: PermissionDialogConfig,
 	pub pending_requests: Arc<Mutex<Vec<ApprovalRequest>>>,
 	pub request_senders: Arc<Mutex<std::collections::HashMap<String, Sender<ApprovalResponse>>>>,
 }
 
+impl Default for PermissionManager {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 impl PermissionManager {
-	pub fn new(config: PermissionDialogConfig) -> Self {
+	pub fn new() -> Self {
 		Self {
-			config,
 			pending_requests: Arc::new(Mutex::new(Vec::new())),
 			request_senders: Arc::new(Mutex::new(std::collections::HashMap::new())),
 		}
@@ -54,21 +117,26 @@ impl PermissionManager {
 		&self,
 		app_handle: &AppHandle,
 		request: ApprovalRequest,
-	) -> Result<ApprovalResponse, String> {
+		config: &PermissionDialogConfig,
+	) -> ApprovalResponse {
 		let request_id = request.request_id.clone();
 
 		// Register response handler
-		let (sender, _receiver) = tokio::sync::oneshot::channel();
+		let (sender, receiver) = tokio::sync::oneshot::channel();
 		{
 			let mut senders = self.request_senders.lock().unwrap();
 			senders.insert(request_id.clone(), sender);
 		}
+		{
+			let mut pending = self.pending_requests.lock().unwrap();
+			pending.push(request.clone());
+		}
 
 		// Check if critical tools always require approval
-		let should_show_dialog = self.config.approval_required_for_critical && request.is_critical
-			|| self.config.show_native_dialog;
+		let should_show_dialog = config.approval_required_for_critical && request.is_critical
+			|| config.show_native_dialog;
 
-		if should_show_dialog {
+		let outcome = if should_show_dialog {
 			// Show native Tauri dialog
 			let message = format!(
 				"Tool Request: {}\n\nDescription: {}\n\nCritical: {}\n\nDo you want to proceed?",
@@ -77,37 +145,55 @@ impl PermissionManager {
 				if request.is_critical { "Yes - Critical Operation" } else { "No - Standard Operation" }
 			);
 
-            let ans = app_handle.dialog()
-                .message(message)
-                .title("Permission Required")
-                .kind(tauri_plugin_dialog::MessageDialogKind::Warning)
-                .blocking_show();
-
-            if ans {
-                Ok(ApprovalResponse::Approved)
-            } else {
-                Ok(ApprovalResponse::Denied)
-            }
+			let app_handle = app_handle.clone();
+			let dialog_task = tokio::task::spawn_blocking(move || {
+				app_handle.dialog()
+					.message(message)
+					.title("Permission Required")
+					.kind(tauri_plugin_dialog::MessageDialogKind::Warning)
+					.blocking_show()
+			});
+
+			// Raced against `receiver` so a concurrent `cancel_request` (which
+			// sends `Cancelled` on this same channel) unblocks this call
+			// immediately instead of waiting out the dialog or the timeout.
+			let timeout = std::time::Duration::from_millis(config.dialog_timeout_ms.max(1));
+			tokio::select! {
+				response = receiver => response.unwrap_or(ApprovalResponse::Error {
+					reason: "approval channel closed before a decision arrived".to_string(),
+				}),
+				result = tokio::time::timeout(timeout, dialog_task) => match result {
+					Ok(Ok(true)) => ApprovalResponse::Approved,
+					Ok(Ok(false)) => ApprovalResponse::Denied,
+					Ok(Err(e)) => ApprovalResponse::Error { reason: e.to_string() },
+					Err(_) => ApprovalResponse::TimedOut,
+				},
+			}
 		} else {
 			// Auto-approve for non-critical tools
 			if request.is_critical {
-				Err("Critical tool requires approval but native dialogs are disabled".to_string())
+				ApprovalResponse::Error {
+					reason: "Critical tool requires approval but native dialogs are disabled".to_string(),
+				}
 			} else {
-				Ok(ApprovalResponse::Approved)
+				ApprovalResponse::Approved
 			}
-		}
+		};
+
+		self.request_senders.lock().unwrap().remove(&request_id);
+		self.pending_requests.lock().unwrap().retain(|r| r.request_id != request_id);
+		outcome
 	}
 
 	pub fn cancel_request(&self, request_id: String) {
-		let mut senders = self.request_senders.lock().unwrap();
-		let mut pending = self.pending_requests.lock().unwrap();
-
-		// Remove from pending requests
-		pending.retain(|r| r.request_id != request_id);
-
-		// Close sender to unblock any waiting receivers
-		if let Some(sender) = senders.remove(&request_id) {
-			drop(sender);
+		let sender = self.request_senders.lock().unwrap().remove(&request_id);
+		self.pending_requests.lock().unwrap().retain(|r| r.request_id != request_id);
+
+		// Send (rather than just drop) so a `request_approval` call racing
+		// this one in `tokio::select!` actually observes `Cancelled`, instead
+		// of merely seeing its channel close with no outcome.
+		if let Some(sender) = sender {
+			let _ = sender.send(ApprovalResponse::Cancelled);
 		}
 	}
 
@@ -121,38 +207,6 @@ fn get_config_path(app: &AppHandle) -> std::path::PathBuf {
     app.path().app_config_dir().unwrap_or_default().join("permissions.json")
 }
 
-/// Tauri command to request approval
-#[tauri::command]
-pub async fn request_tool_approval(
-	app: AppHandle,
-	request: ApprovalRequest,
-) -> Result<bool, String> {
-	let config_path = get_config_path(&app);
-	let config = match std::fs::read_to_string(&config_path) {
-		Ok(content) => serde_json::from_str::<PermissionDialogConfig>(&content).unwrap_or_default(),
-		Err(_) => {
-			eprintln!("Failed to read permissions config, using defaults");
-			PermissionDialogConfig::default()
-		}
-	};
-
-	let permission_manager = Arc::new(PermissionManager::new(config));
-	let manager_clone = permission_manager.clone();
-	let request_clone = request.clone();
-
-	// Run approval request in background task
-	let result = tokio::task::spawn_blocking(move || {
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        rt.block_on(manager_clone.request_approval(&app, request_clone))
-	}).await.map_err(|e| e.to_string())?;
-
-	match result {
-		Ok(ApprovalResponse::Approved) => Ok(true),
-		Ok(ApprovalResponse::Denied) | Ok(ApprovalResponse::Cancelled) => Ok(false),
-		Err(e) => Err(e),
-	}
-}
-
 /// Tauri command to configure permissions
 #[tauri::command]
 pub async fn configure_permissions(
@@ -171,41 +225,6 @@ pub async fn configure_permissions(
 	Ok(())
 }
 
-/// Tauri command to list pending approvals
-#[tauri::command]
-pub async fn list_pending_approvals(app: AppHandle) -> Vec<ApprovalRequest> {
-	let config_path = get_config_path(&app);
-	let config = match std::fs::read_to_string(&config_path) {
-		Ok(content) => serde_json::from_str::<PermissionDialogConfig>(&content).unwrap_or_default(),
-		Err(_) => {
-			eprintln!("Failed to read permissions config, using defaults");
-			PermissionDialogConfig::default()
-		}
-	};
-
-	let manager = Arc::new(PermissionManager::new(config));
-	manager.list_pending()
-}
-
-/// Tauri command to cancel an approval request
-#[tauri::command]
-pub async fn cancel_approval(app: AppHandle, request_id: String) -> Result<(), String> {
-	let config_path = get_config_path(&app);
-	let config = match std::fs::read_to_string(&config_path) {
-		Ok(content) => serde_json::from_str::<PermissionDialogConfig>(&content).unwrap_or_default(),
-		Err(_) => {
-			eprintln!("Failed to read permissions config, using defaults");
-			PermissionDialogConfig::default()
-		}
-	};
-
-	let manager = Arc::new(PermissionManager::new(config));
-	eprintln!("Cancelled approval request: {}", request_id);
-	manager.cancel_request(request_id);
-
-	Ok(())
-}
-
 /// Tauri command to check if native dialogs are supported on this platform
 #[tauri::command]
 pub async fn check_native_dialog_support(_app: AppHandle) -> bool {
@@ -247,13 +266,8 @@ mod tests {
 
     #[test]
     fn test_manager_initialization() {
-        let config = PermissionDialogConfig {
-            show_native_dialog: true,
-            dialog_timeout_ms: 5000,
-            approval_required_for_critical: true,
-        };
-        let manager = PermissionManager::new(config);
-        
+        let manager = PermissionManager::new();
+
         let pending = manager.list_pending();
         assert!(pending.is_empty());
     }
@@ -272,4 +286,26 @@ mod tests {
         assert_eq!(req.tool_name, "test-tool");
         assert!(req.is_critical);
     }
+
+    #[tokio::test]
+    async fn test_cancel_request_unblocks_pending_sender_with_cancelled() {
+        let manager = PermissionManager::new();
+        let request = ApprovalRequest {
+            request_id: "req-cancel".into(),
+            tool_name: "test-tool".into(),
+            tool_description: "test description".into(),
+            args: serde_json::json!({}),
+            session_id: "sess-1".into(),
+            is_critical: true,
+        };
+
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        manager.request_senders.lock().unwrap().insert(request.request_id.clone(), sender);
+        manager.pending_requests.lock().unwrap().push(request.clone());
+
+        manager.cancel_request(request.request_id.clone());
+
+        assert!(matches!(receiver.await, Ok(ApprovalResponse::Cancelled)));
+        assert!(manager.list_pending().is_empty());
+    }
 }
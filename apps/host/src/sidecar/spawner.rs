@@ -0,0 +1,314 @@
+//! Injectable process spawning for the sidecar.
+//!
+//! `spawn_sidecar` used to build and spawn a `tokio::process::Command`
+//! directly, which meant exercising `monitor()`'s restart/backoff loop or
+//! `call_rpc`'s timeout/channel-closed paths required launching a real
+//! `pryx-core` binary. [`ProcessSpawner`] extracts that one call behind a
+//! trait returning a [`ChildHandle`], so tests can swap in
+//! [`MockProcessSpawner`] to script clean exits, crashes with arbitrary exit
+//! codes, and canned JSON-RPC lines on a fake stdout.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// Everything [`ProcessSpawner::spawn`] needs to launch the sidecar; mirrors
+/// the fields `spawn_sidecar` used to set directly on a `Command`.
+#[derive(Debug, Clone)]
+pub struct ProcessSpec {
+    pub binary: PathBuf,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub cwd: PathBuf,
+}
+
+/// A running (or exited) child process, abstracted over the concrete
+/// `tokio::process::Child` so tests can substitute [`MockChildHandle`].
+#[async_trait::async_trait]
+pub trait ChildHandle: Send + std::fmt::Debug {
+    fn id(&self) -> Option<u32>;
+
+    /// Non-blocking poll for exit, mirroring `Child::try_wait`.
+    fn try_wait(&mut self) -> io::Result<Option<ExitStatus>>;
+
+    /// Block until the child exits.
+    async fn wait(&mut self) -> io::Result<ExitStatus>;
+
+    /// Hard-kill just this process.
+    fn kill(&mut self) -> io::Result<()>;
+
+    /// Send `signal` to the process group on Unix (used for the
+    /// SIGTERM-then-SIGKILL sequence in [`super::SidecarProcess::stop`]);
+    /// falls back to [`Self::kill`] on platforms without process groups.
+    fn killpg(&mut self, signal: i32) -> io::Result<()>;
+
+    fn take_stdin(&mut self) -> Option<Box<dyn AsyncWrite + Send + Unpin>>;
+    fn take_stdout(&mut self) -> Option<Box<dyn AsyncRead + Send + Unpin>>;
+    fn take_stderr(&mut self) -> Option<Box<dyn AsyncRead + Send + Unpin>>;
+}
+
+/// Spawns real OS processes. The production implementation used by
+/// [`super::SidecarProcess`] outside of tests.
+pub trait ProcessSpawner: Send + Sync + std::fmt::Debug {
+    fn spawn(&self, spec: &ProcessSpec) -> io::Result<Box<dyn ChildHandle>>;
+}
+
+/// `ProcessSpawner` backed by `tokio::process::Command`, exactly matching
+/// what `spawn_sidecar` used to do inline: pipe all three streams and, on
+/// Unix, put the child in its own process group so [`ChildHandle::killpg`]
+/// can stop it (and anything it forked) in one shot.
+#[derive(Debug, Default)]
+pub struct RealProcessSpawner;
+
+impl ProcessSpawner for RealProcessSpawner {
+    fn spawn(&self, spec: &ProcessSpec) -> io::Result<Box<dyn ChildHandle>> {
+        let mut cmd = tokio::process::Command::new(&spec.binary);
+        cmd.args(&spec.args);
+        cmd.current_dir(&spec.cwd);
+        for (k, v) in &spec.env {
+            cmd.env(k, v);
+        }
+        cmd.stdout(std::process::Stdio::piped());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdin(std::process::Stdio::piped());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            unsafe {
+                cmd.pre_exec(|| {
+                    if libc::setpgid(0, 0) != 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                    Ok(())
+                });
+            }
+        }
+
+        let child = cmd.spawn()?;
+        Ok(Box::new(RealChildHandle(child)))
+    }
+}
+
+#[derive(Debug)]
+struct RealChildHandle(tokio::process::Child);
+
+#[async_trait::async_trait]
+impl ChildHandle for RealChildHandle {
+    fn id(&self) -> Option<u32> {
+        self.0.id()
+    }
+
+    fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        self.0.try_wait()
+    }
+
+    async fn wait(&mut self) -> io::Result<ExitStatus> {
+        self.0.wait().await
+    }
+
+    fn kill(&mut self) -> io::Result<()> {
+        self.0.start_kill()
+    }
+
+    fn killpg(&mut self, signal: i32) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            let Some(pid) = self.0.id() else {
+                return Ok(());
+            };
+            unsafe {
+                if libc::killpg(pid as i32, signal) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = signal;
+            self.kill()
+        }
+    }
+
+    fn take_stdin(&mut self) -> Option<Box<dyn AsyncWrite + Send + Unpin>> {
+        self.0.stdin.take().map(|s| Box::new(s) as Box<dyn AsyncWrite + Send + Unpin>)
+    }
+
+    fn take_stdout(&mut self) -> Option<Box<dyn AsyncRead + Send + Unpin>> {
+        self.0.stdout.take().map(|s| Box::new(s) as Box<dyn AsyncRead + Send + Unpin>)
+    }
+
+    fn take_stderr(&mut self) -> Option<Box<dyn AsyncRead + Send + Unpin>> {
+        self.0.stderr.take().map(|s| Box::new(s) as Box<dyn AsyncRead + Send + Unpin>)
+    }
+}
+
+#[cfg(test)]
+pub use mock::*;
+
+#[cfg(test)]
+mod mock {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    /// What a scripted [`MockChildHandle`] should do once `monitor()` starts
+    /// polling it with `try_wait`.
+    #[derive(Debug, Clone)]
+    pub enum MockOutcome {
+        /// Never exits on its own; a test ends the run by calling
+        /// [`MockChildHandle::finish`] directly, or `monitor()` sees it as
+        /// indefinitely running.
+        RunsForever,
+        /// Exits cleanly (code 0) the first time it's polled.
+        CleanExit,
+        /// Exits with the given exit code the first time it's polled.
+        CrashExitCode(i32),
+        /// Exits via the given signal the first time it's polled (Unix only).
+        CrashSignal(i32),
+    }
+
+    /// Queues up the behavior of successive `spawn()` calls, so a test can
+    /// script e.g. "crash, crash, then run forever" to exercise
+    /// `monitor()`'s restart/backoff/circuit-breaker paths deterministically.
+    #[derive(Debug, Default)]
+    pub struct MockProcessSpawner {
+        scripted: Mutex<VecDeque<(MockOutcome, Vec<String>)>>,
+    }
+
+    impl MockProcessSpawner {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Queue the next `spawn()` call's outcome and the JSON-RPC lines its
+        /// fake stdout should emit before (or instead of) exiting.
+        pub fn push(&self, outcome: MockOutcome, stdout_lines: Vec<String>) {
+            self.scripted.lock().unwrap().push_back((outcome, stdout_lines));
+        }
+    }
+
+    impl ProcessSpawner for MockProcessSpawner {
+        fn spawn(&self, _spec: &ProcessSpec) -> io::Result<Box<dyn ChildHandle>> {
+            let (outcome, stdout_lines) = self
+                .scripted
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or((MockOutcome::RunsForever, Vec::new()));
+
+            Ok(Box::new(MockChildHandle::new(outcome, stdout_lines)))
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct MockChildHandle {
+        outcome: MockOutcome,
+        exited: Arc<Mutex<Option<ExitStatus>>>,
+        stdout_lines: Option<Vec<String>>,
+        killed: Arc<Mutex<bool>>,
+    }
+
+    impl MockChildHandle {
+        fn new(outcome: MockOutcome, stdout_lines: Vec<String>) -> Self {
+            Self {
+                outcome,
+                exited: Arc::new(Mutex::new(None)),
+                stdout_lines: Some(stdout_lines),
+                killed: Arc::new(Mutex::new(false)),
+            }
+        }
+
+        #[cfg(unix)]
+        fn synthesize_exit(outcome: &MockOutcome) -> Option<ExitStatus> {
+            use std::os::unix::process::ExitStatusExt;
+            match outcome {
+                MockOutcome::RunsForever => None,
+                MockOutcome::CleanExit => Some(ExitStatus::from_raw(0)),
+                MockOutcome::CrashExitCode(code) => Some(ExitStatus::from_raw(code << 8)),
+                MockOutcome::CrashSignal(signal) => Some(ExitStatus::from_raw(*signal)),
+            }
+        }
+
+        pub fn was_killed(&self) -> bool {
+            *self.killed.lock().unwrap()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ChildHandle for MockChildHandle {
+        fn id(&self) -> Option<u32> {
+            Some(1)
+        }
+
+        fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+            #[cfg(unix)]
+            {
+                if self.exited.lock().unwrap().is_none() {
+                    *self.exited.lock().unwrap() = Self::synthesize_exit(&self.outcome);
+                }
+            }
+            Ok(*self.exited.lock().unwrap())
+        }
+
+        async fn wait(&mut self) -> io::Result<ExitStatus> {
+            loop {
+                if let Some(status) = self.try_wait()? {
+                    return Ok(status);
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        }
+
+        fn kill(&mut self) -> io::Result<()> {
+            *self.killed.lock().unwrap() = true;
+            #[cfg(unix)]
+            {
+                use std::os::unix::process::ExitStatusExt;
+                *self.exited.lock().unwrap() = Some(ExitStatus::from_raw(libc::SIGKILL));
+            }
+            Ok(())
+        }
+
+        fn killpg(&mut self, _signal: i32) -> io::Result<()> {
+            self.kill()
+        }
+
+        fn take_stdin(&mut self) -> Option<Box<dyn AsyncWrite + Send + Unpin>> {
+            let (_write, read) = tokio::io::duplex(4096);
+            // Nothing consumes the write half in tests today; dropping it
+            // immediately closes the pipe, which is fine since nothing reads
+            // from `read` either. Kept as a real AsyncWrite so call sites
+            // that write to "stdin" don't error.
+            Some(Box::new(read))
+        }
+
+        fn take_stdout(&mut self) -> Option<Box<dyn AsyncRead + Send + Unpin>> {
+            let lines = self.stdout_lines.take()?;
+            let (mut writer, reader) = tokio::io::duplex(65536);
+            tokio::spawn(async move {
+                use tokio::io::AsyncWriteExt;
+                for line in lines {
+                    if writer.write_all(line.as_bytes()).await.is_err() {
+                        return;
+                    }
+                    if writer.write_all(b"\n").await.is_err() {
+                        return;
+                    }
+                }
+                // Dropping `writer` here closes the pipe, so the reader
+                // side sees EOF after the canned lines.
+            });
+            Some(Box::new(reader))
+        }
+
+        fn take_stderr(&mut self) -> Option<Box<dyn AsyncRead + Send + Unpin>> {
+            let (_writer, reader) = tokio::io::duplex(4096);
+            Some(Box::new(reader))
+        }
+    }
+}
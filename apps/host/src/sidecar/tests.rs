@@ -1,13 +1,13 @@
 #[cfg(test)]
 mod tests {
     use crate::sidecar::SidecarConfig;
-    use crate::sidecar::permissions::{PermissionManager, PermissionDialogConfig};
+    use crate::sidecar::permissions::PermissionManager;
     use std::path::PathBuf;
 
     #[test]
     fn test_default_config() {
         let config = SidecarConfig::default();
-        // sidecar_port/grpc_port are likely dynamically assigned or in env, 
+        // sidecar_port/grpc_port are likely dynamically assigned or in env,
         // SidecarConfig struct has: binary, args, env, cwd, db_path, etc.
         assert_eq!(config.binary.to_string_lossy(), "pryx-core");
         assert_eq!(config.db_path.to_string_lossy(), "pryx.db");
@@ -22,10 +22,416 @@ mod tests {
 
     #[test]
     fn test_permission_manager_initial_state() {
-        let config = PermissionDialogConfig::default();
-        let manager = PermissionManager::new(config);
-        
+        let manager = PermissionManager::new();
+
         let pending = manager.list_pending();
         assert!(pending.is_empty());
     }
+
+    #[test]
+    fn test_subscriptions_fan_out_by_topic_only() {
+        let mut subs = super::super::Subscriptions::default();
+        subs.subscribe("updater.progress", 1);
+        subs.subscribe("updater.progress", 2);
+        subs.subscribe("process.output", 3);
+
+        let mut updater_subs: Vec<u64> = subs.subscribers("updater.progress").copied().collect();
+        updater_subs.sort();
+        assert_eq!(updater_subs, vec![1, 2]);
+        assert_eq!(subs.subscribers("process.output").copied().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(subs.subscribers("nobody.home").count(), 0);
+
+        assert!(subs.unsubscribe(1));
+        assert_eq!(subs.subscribers("updater.progress").copied().collect::<Vec<_>>(), vec![2]);
+        assert!(!subs.unsubscribe(1));
+    }
+}
+
+/// Mock-driven tests of `monitor()`'s restart/backoff/circuit-breaker loop,
+/// `call_rpc`'s timeout/channel-closed paths, and the `health.ping` liveness
+/// probe, made possible by injecting a `MockProcessSpawner` via
+/// [`super::SidecarProcess::new_with_spawner`] instead of launching a real
+/// `pryx-core` binary.
+#[cfg(test)]
+mod supervision_tests {
+    use super::super::spawner::{MockOutcome, MockProcessSpawner};
+    use super::super::transport::{Frame, Transport};
+    use super::super::{SidecarConfig, SidecarProcess, SidecarState};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::{oneshot, Mutex as AsyncMutex};
+
+    fn test_app_handle() -> tauri::AppHandle {
+        tauri::test::mock_app().handle().clone()
+    }
+
+    fn fast_restart_config() -> SidecarConfig {
+        let mut config =
+            SidecarConfig::new(PathBuf::from("fake-core"), PathBuf::from("."), PathBuf::from("fake.db"));
+        // Short enough that `start()`'s port-discovery timeout fires well
+        // before the test would otherwise, without waiting out the full
+        // `port_discovery_timeout_secs` (minimum 1s, since it's whole seconds).
+        config.start_timeout = Duration::from_millis(20);
+        config.initial_backoff_ms = 1;
+        config.backoff_multiplier = 1.0;
+        config.max_backoff_ms = 5;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_monitor_restarts_until_max_restarts_then_crashed() {
+        let mut config = fast_restart_config();
+        config.max_restarts = 2;
+        // High enough that this test exercises `max_restarts`, not the
+        // circuit breaker (covered separately below).
+        config.circuit_breaker_threshold = 100;
+
+        let spawner = Arc::new(MockProcessSpawner::new());
+        for _ in 0..(config.max_restarts + 1) {
+            spawner.push(MockOutcome::CrashExitCode(1), vec![]);
+        }
+
+        let process = Arc::new(SidecarProcess::new_with_spawner(
+            config,
+            test_app_handle(),
+            spawner,
+        ));
+
+        process.start().await.unwrap();
+        process.monitor().await;
+
+        match process.state() {
+            SidecarState::Crashed { attempts } => assert_eq!(attempts, 3),
+            other => panic!("expected Crashed after max_restarts, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_monitor_trips_circuit_breaker_within_window() {
+        let mut config = fast_restart_config();
+        config.max_restarts = 0; // unlimited, so only the breaker can stop it
+        config.circuit_breaker_threshold = 2;
+        config.circuit_breaker_window_secs = 60;
+
+        let spawner = Arc::new(MockProcessSpawner::new());
+        for _ in 0..3 {
+            spawner.push(MockOutcome::CrashExitCode(1), vec![]);
+        }
+
+        let process = Arc::new(SidecarProcess::new_with_spawner(
+            config,
+            test_app_handle(),
+            spawner,
+        ));
+
+        process.start().await.unwrap();
+        process.monitor().await;
+
+        assert!(process.circuit_open());
+        assert!(matches!(process.state(), SidecarState::Crashed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_monitor_does_not_restart_on_clean_exit() {
+        let config = fast_restart_config();
+
+        let spawner = Arc::new(MockProcessSpawner::new());
+        spawner.push(MockOutcome::CleanExit, vec![]);
+
+        let process = Arc::new(SidecarProcess::new_with_spawner(
+            config,
+            test_app_handle(),
+            spawner,
+        ));
+
+        process.start().await.unwrap();
+        process.monitor().await;
+
+        assert_eq!(process.state(), SidecarState::Stopped);
+    }
+
+    /// A [`Transport`] that never answers: `recv` hangs forever, so the only
+    /// way a `call_rpc` completes is by timing out.
+    #[derive(Debug)]
+    struct NeverRespondingTransport;
+
+    #[async_trait::async_trait]
+    impl Transport for NeverRespondingTransport {
+        async fn send(&self, _frame: Frame) -> std::io::Result<()> {
+            Ok(())
+        }
+
+        async fn recv(&self) -> std::io::Result<Frame> {
+            std::future::pending().await
+        }
+    }
+
+    /// A [`Transport`] whose `send` drops the matching pending request
+    /// instead of ever answering it, simulating the sidecar's end of the
+    /// connection closing mid-request.
+    #[derive(Debug)]
+    struct ConnectionResetTransport {
+        pending: Arc<AsyncMutex<HashMap<u64, oneshot::Sender<Result<serde_json::Value, String>>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for ConnectionResetTransport {
+        async fn send(&self, frame: Frame) -> std::io::Result<()> {
+            let req: serde_json::Value = serde_json::from_slice(&frame.payload).unwrap();
+            if let Some(id) = req.get("id").and_then(|v| v.as_u64()) {
+                self.pending.lock().await.remove(&id);
+            }
+            Ok(())
+        }
+
+        async fn recv(&self) -> std::io::Result<Frame> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_call_rpc_times_out_when_sidecar_never_responds() {
+        let process = Arc::new(SidecarProcess::new_with_spawner(
+            SidecarConfig::default(),
+            test_app_handle(),
+            Arc::new(MockProcessSpawner::new()),
+        ));
+        *process.transport.lock().await = Some(Arc::new(NeverRespondingTransport));
+
+        let call = {
+            let process = process.clone();
+            tokio::spawn(async move {
+                process.call_rpc("admin.health", serde_json::json!({})).await
+            })
+        };
+
+        tokio::time::advance(Duration::from_secs(11)).await;
+        let result = call.await.unwrap();
+
+        let err = result.expect_err("expected the RPC call to time out");
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_call_rpc_errors_when_response_channel_closes() {
+        let process = Arc::new(SidecarProcess::new_with_spawner(
+            SidecarConfig::default(),
+            test_app_handle(),
+            Arc::new(MockProcessSpawner::new()),
+        ));
+        let pending = process.pending_requests.clone();
+        *process.transport.lock().await = Some(Arc::new(ConnectionResetTransport { pending }));
+
+        let result = process.call_rpc("admin.health", serde_json::json!({})).await;
+
+        let err = result.expect_err("expected the RPC call to fail");
+        assert!(err.to_string().contains("channel closed"));
+    }
+
+    /// A [`Transport`] that immediately answers every request with a canned
+    /// success value, simulating a healthy sidecar for `health.ping` probes.
+    #[derive(Debug)]
+    struct AlwaysHealthyTransport {
+        pending: Arc<AsyncMutex<HashMap<u64, oneshot::Sender<Result<serde_json::Value, String>>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for AlwaysHealthyTransport {
+        async fn send(&self, frame: Frame) -> std::io::Result<()> {
+            let req: serde_json::Value = serde_json::from_slice(&frame.payload).unwrap();
+            if let Some(id) = req.get("id").and_then(|v| v.as_u64()) {
+                if let Some(tx) = self.pending.lock().await.remove(&id) {
+                    let _ = tx.send(Ok(serde_json::json!({"status": "ok"})));
+                }
+            }
+            Ok(())
+        }
+
+        async fn recv(&self) -> std::io::Result<Frame> {
+            std::future::pending().await
+        }
+    }
+
+    /// A [`Transport`] that answers every request with a JSON-RPC error
+    /// object, so `call_rpc` must surface the sidecar's error message rather
+    /// than resolving as if it were a successful `null` result.
+    #[derive(Debug)]
+    struct AlwaysErroringTransport {
+        pending: Arc<AsyncMutex<HashMap<u64, oneshot::Sender<Result<serde_json::Value, String>>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for AlwaysErroringTransport {
+        async fn send(&self, frame: Frame) -> std::io::Result<()> {
+            let req: serde_json::Value = serde_json::from_slice(&frame.payload).unwrap();
+            if let Some(id) = req.get("id").and_then(|v| v.as_u64()) {
+                if let Some(tx) = self.pending.lock().await.remove(&id) {
+                    let _ = tx.send(Err("method not found".to_string()));
+                }
+            }
+            Ok(())
+        }
+
+        async fn recv(&self) -> std::io::Result<Frame> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_rpc_surfaces_sidecar_error() {
+        let process = Arc::new(SidecarProcess::new_with_spawner(
+            SidecarConfig::default(),
+            test_app_handle(),
+            Arc::new(MockProcessSpawner::new()),
+        ));
+        let pending = process.pending_requests.clone();
+        *process.transport.lock().await = Some(Arc::new(AlwaysErroringTransport { pending }));
+
+        let result = process.call_rpc("admin.health", serde_json::json!({})).await;
+
+        let err = result.expect_err("expected the sidecar's error to propagate");
+        assert!(err.to_string().contains("method not found"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_health_probe_failures_accumulate_to_threshold() {
+        let mut config = fast_restart_config();
+        config.health_probe_timeout_ms = 50;
+        config.health_probe_failure_threshold = 2;
+
+        let process = Arc::new(SidecarProcess::new_with_spawner(
+            config,
+            test_app_handle(),
+            Arc::new(MockProcessSpawner::new()),
+        ));
+        *process.transport.lock().await = Some(Arc::new(NeverRespondingTransport));
+
+        for attempt in 1..=2 {
+            let probe = {
+                let process = process.clone();
+                tokio::spawn(async move { process.run_health_probe().await })
+            };
+            tokio::time::advance(Duration::from_millis(100)).await;
+            probe.await.unwrap();
+
+            assert_eq!(attempt >= 2, process.probe_failures_exceeded());
+        }
+        assert!(!process.status().last_health_ok);
+        assert_eq!(process.status().consecutive_probe_failures, 2);
+    }
+
+    #[tokio::test]
+    async fn test_health_probe_success_resets_failure_count() {
+        let config = fast_restart_config();
+        let process = Arc::new(SidecarProcess::new_with_spawner(
+            config,
+            test_app_handle(),
+            Arc::new(MockProcessSpawner::new()),
+        ));
+        *process.consecutive_probe_failures.lock().unwrap() = 3;
+        *process.last_health_ok.lock().unwrap() = false;
+
+        let pending = process.pending_requests.clone();
+        *process.transport.lock().await = Some(Arc::new(AlwaysHealthyTransport { pending }));
+
+        process.run_health_probe().await;
+
+        let status = process.status();
+        assert!(status.last_health_ok);
+        assert_eq!(status.consecutive_probe_failures, 0);
+        assert!(!process.probe_failures_exceeded());
+    }
+
+    /// A [`Transport`] that answers `initialize` with a caller-supplied
+    /// version, simulating a core that negotiated (or lied about) a
+    /// specific protocol version.
+    #[derive(Debug)]
+    struct FixedVersionTransport {
+        pending: Arc<AsyncMutex<HashMap<u64, oneshot::Sender<Result<serde_json::Value, String>>>>>,
+        version: u32,
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for FixedVersionTransport {
+        async fn send(&self, frame: Frame) -> std::io::Result<()> {
+            let req: serde_json::Value = serde_json::from_slice(&frame.payload).unwrap();
+            if let Some(id) = req.get("id").and_then(|v| v.as_u64()) {
+                if let Some(tx) = self.pending.lock().await.remove(&id) {
+                    let _ = tx.send(Ok(serde_json::json!({
+                        "version": self.version,
+                        "min": self.version,
+                        "max": self.version,
+                        "capabilities": [],
+                    })));
+                }
+            }
+            Ok(())
+        }
+
+        async fn recv(&self) -> std::io::Result<Frame> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_protocol_rejects_version_outside_host_range() {
+        let process = Arc::new(SidecarProcess::new_with_spawner(
+            SidecarConfig::default(),
+            test_app_handle(),
+            Arc::new(MockProcessSpawner::new()),
+        ));
+        let pending = process.pending_requests.clone();
+        *process.transport.lock().await =
+            Some(Arc::new(FixedVersionTransport { pending, version: 99 }));
+
+        let ok = process.negotiate_protocol().await.unwrap();
+
+        assert!(!ok);
+        assert!(matches!(process.state(), SidecarState::Incompatible { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_protocol_accepts_version_inside_host_range() {
+        let process = Arc::new(SidecarProcess::new_with_spawner(
+            SidecarConfig::default(),
+            test_app_handle(),
+            Arc::new(MockProcessSpawner::new()),
+        ));
+        let pending = process.pending_requests.clone();
+        *process.transport.lock().await =
+            Some(Arc::new(FixedVersionTransport { pending, version: 2 }));
+
+        let ok = process.negotiate_protocol().await.unwrap();
+
+        assert!(ok);
+        assert!(!matches!(process.state(), SidecarState::Incompatible { .. }));
+    }
+
+    #[test]
+    fn test_decorrelated_jitter_backoff_stays_within_bounds_and_resets() {
+        let mut config = fast_restart_config();
+        config.backoff_strategy = super::super::BackoffStrategy::DecorrelatedJitter;
+        config.initial_backoff_ms = 10;
+        config.max_backoff_ms = 50;
+        let process = SidecarProcess::new_with_spawner(
+            config,
+            test_app_handle(),
+            Arc::new(MockProcessSpawner::new()),
+        );
+
+        for attempt in 1..=5 {
+            let backoff = process.calculate_backoff(attempt);
+            assert!(
+                (10..=50).contains(&backoff),
+                "backoff {} out of [initial_backoff_ms, max_backoff_ms]",
+                backoff
+            );
+        }
+
+        *process.backoff_prev_ms.lock().unwrap() = 50;
+        process.reset_backoff();
+        assert_eq!(*process.backoff_prev_ms.lock().unwrap(), 10);
+    }
 }
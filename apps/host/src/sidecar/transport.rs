@@ -0,0 +1,365 @@
+//! Pluggable transports for the sidecar RPC channel.
+//!
+//! The original protocol multiplexed human log lines and JSON-RPC on the
+//! same stdout stream, disambiguated only by `line.trim().starts_with('{')`,
+//! which breaks on multi-line JSON or log lines that happen to start with a
+//! brace. [`Transport`] separates "how a frame is sent/received" from the
+//! rest of [`super::SidecarProcess`], so RPC can move off stdout entirely
+//! onto a dedicated channel without touching the call sites in `call_rpc`,
+//! `send_response`, or `send_notification`.
+
+use std::io;
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex as AsyncMutex;
+use tokio::sync::mpsc;
+
+/// Opcode for a JSON-RPC payload. The only opcode in use today; modeled after
+/// the Discord RPC IPC protocol, which reserves others for handshake/close/
+/// ping frames we don't need here since our own `initialize` RPC already
+/// covers the handshake.
+pub const OPCODE_RPC: u32 = 1;
+
+/// How [`StdioTransport`] delimits JSON-RPC messages on the wire.
+///
+/// `LineDelimited` is the original behavior: one message per line, which
+/// breaks on a payload containing an embedded newline (e.g. a multi-line
+/// string field) and gives the reader no way to know a message is complete
+/// before it sees `\n`. `ContentLength` instead prefixes each message with
+/// an LSP-style `Content-Length: <bytes>\r\n\r\n` header naming its exact
+/// byte length, so the reader can read precisely that many bytes regardless
+/// of what they contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameCodec {
+    LineDelimited,
+    /// The default: immune to a log line that happens to start with `{` or
+    /// to a multi-line/pretty-printed payload, since framing is driven by
+    /// the declared byte count rather than looking at the content itself.
+    #[default]
+    ContentLength,
+}
+
+/// How a [`Frame`]'s payload bytes are serialized, independent of how the
+/// frame is delimited on the wire (see [`FrameCodec`]). JSON is the
+/// original, human-readable format and the only one safe to pair with
+/// [`FrameCodec::LineDelimited`] (the binary formats can contain an
+/// embedded `\n` byte, which that framing mistakes for a message boundary);
+/// the rest trade readability for throughput on high-frequency core traffic
+/// like streamed tool output. Selected once via `SidecarConfig::wire_codec`
+/// and announced to the core in the `initialize` handshake, not renegotiated
+/// mid-connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WireCodec {
+    #[default]
+    Json,
+    MessagePack,
+    Bincode,
+    Postcard,
+}
+
+impl WireCodec {
+    /// Serialize `value` to this codec's wire representation.
+    pub fn encode(&self, value: &Value) -> io::Result<Vec<u8>> {
+        match self {
+            WireCodec::Json => serde_json::to_vec(value).map_err(encode_error),
+            WireCodec::MessagePack => rmp_serde::to_vec(value).map_err(encode_error),
+            WireCodec::Bincode => bincode::serialize(value).map_err(encode_error),
+            WireCodec::Postcard => postcard::to_allocvec(value).map_err(encode_error),
+        }
+    }
+
+    /// Deserialize a payload previously produced by [`Self::encode`] back
+    /// into the [`Value`] the rest of the sidecar module works with,
+    /// regardless of which codec actually produced it.
+    pub fn decode(&self, bytes: &[u8]) -> io::Result<Value> {
+        match self {
+            WireCodec::Json => serde_json::from_slice(bytes).map_err(encode_error),
+            WireCodec::MessagePack => rmp_serde::from_slice(bytes).map_err(encode_error),
+            WireCodec::Bincode => bincode::deserialize(bytes).map_err(encode_error),
+            WireCodec::Postcard => postcard::from_bytes(bytes).map_err(encode_error),
+        }
+    }
+}
+
+fn encode_error(e: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+/// A message exchanged over a [`Transport`]. `opcode` exists so a framed
+/// transport can eventually carry non-RPC traffic over the same channel;
+/// every frame produced by this module today is [`OPCODE_RPC`].
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub opcode: u32,
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    pub fn rpc(payload: Vec<u8>) -> Self {
+        Self {
+            opcode: OPCODE_RPC,
+            payload,
+        }
+    }
+}
+
+/// Send/receive of framed RPC messages between host and sidecar, independent
+/// of how the frame is physically carried. [`StdioTransport`] multiplexes
+/// RPC onto the same newline-delimited stdin/stdout the sidecar already logs
+/// on; [`FramedTransport`] carries length-prefixed frames over a dedicated
+/// Unix domain socket / named pipe so large payloads and diagnostic logging
+/// never contend on the same stream.
+#[async_trait::async_trait]
+pub trait Transport: Send + Sync + std::fmt::Debug {
+    async fn send(&self, frame: Frame) -> io::Result<()>;
+    async fn recv(&self) -> io::Result<Frame>;
+}
+
+/// Newline-delimited JSON over the child's existing stdin, exactly as the
+/// sidecar protocol worked before framed transports existed. The stdout side
+/// is read elsewhere (`spawn_sidecar`'s log/port-discovery loop), which hands
+/// any JSON-looking line to this transport's internal channel rather than
+/// owning a reader itself, so port discovery and RPC can keep sharing one
+/// stdout stream.
+pub struct StdioTransport {
+    stdin: Arc<AsyncMutex<Option<Box<dyn tokio::io::AsyncWrite + Send + Unpin>>>>,
+    frames_rx: AsyncMutex<mpsc::Receiver<Frame>>,
+    codec: FrameCodec,
+}
+
+impl std::fmt::Debug for StdioTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StdioTransport").finish_non_exhaustive()
+    }
+}
+
+impl StdioTransport {
+    pub fn new(
+        stdin: Arc<AsyncMutex<Option<Box<dyn tokio::io::AsyncWrite + Send + Unpin>>>>,
+        frames_rx: mpsc::Receiver<Frame>,
+        codec: FrameCodec,
+    ) -> Self {
+        Self {
+            stdin,
+            frames_rx: AsyncMutex::new(frames_rx),
+            codec,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for StdioTransport {
+    async fn send(&self, frame: Frame) -> io::Result<()> {
+        let mut guard = self.stdin.lock().await;
+        let stdin = guard.as_mut().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotConnected, "sidecar stdin not available")
+        })?;
+        match self.codec {
+            FrameCodec::LineDelimited => {
+                stdin.write_all(&frame.payload).await?;
+                stdin.write_all(b"\n").await?;
+            }
+            FrameCodec::ContentLength => {
+                let header = format!("Content-Length: {}\r\n\r\n", frame.payload.len());
+                stdin.write_all(header.as_bytes()).await?;
+                stdin.write_all(&frame.payload).await?;
+            }
+        }
+        stdin.flush().await
+    }
+
+    async fn recv(&self) -> io::Result<Frame> {
+        self.frames_rx.lock().await.recv().await.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::UnexpectedEof, "sidecar stdout closed")
+        })
+    }
+}
+
+/// Length-prefixed JSON over a dedicated Unix domain socket (or Windows
+/// named pipe), modeled on the Discord RPC IPC wire format: a little-endian
+/// `u32` opcode, a little-endian `u32` payload length, then the UTF-8 JSON
+/// body. The host binds the socket and passes its path to the sidecar via
+/// an env var; the sidecar connects in. Diagnostic logging stays on
+/// stdout/stderr, so this channel only ever carries RPC frames.
+pub struct FramedTransport {
+    #[cfg(unix)]
+    stream: AsyncMutex<tokio::net::UnixStream>,
+    #[cfg(windows)]
+    stream: AsyncMutex<tokio::net::windows::named_pipe::NamedPipeServer>,
+}
+
+impl std::fmt::Debug for FramedTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FramedTransport").finish_non_exhaustive()
+    }
+}
+
+impl FramedTransport {
+    /// Bind `path` and wait for the sidecar's single inbound connection.
+    /// Removes any stale socket file left behind by a previous run first.
+    #[cfg(unix)]
+    pub async fn listen_and_accept(path: &std::path::Path) -> io::Result<Self> {
+        let _ = std::fs::remove_file(path);
+        let listener = tokio::net::UnixListener::bind(path)?;
+        let (stream, _addr) = listener.accept().await?;
+        Ok(Self {
+            stream: AsyncMutex::new(stream),
+        })
+    }
+
+    #[cfg(windows)]
+    pub async fn listen_and_accept(path: &std::path::Path) -> io::Result<Self> {
+        let server = tokio::net::windows::named_pipe::ServerOptions::new()
+            .create(path.to_string_lossy().as_ref())?;
+        server.connect().await?;
+        Ok(Self {
+            stream: AsyncMutex::new(server),
+        })
+    }
+}
+
+/// Parse a `Content-Length: <bytes>` header line (case-insensitive, as LSP
+/// requires), returning the byte count it names. Any other line (a
+/// differently-cased/ordered header, or a plain log line) yields `None`.
+pub fn parse_content_length(line: &str) -> Option<usize> {
+    let (name, value) = line.split_once(':')?;
+    if !name.trim().eq_ignore_ascii_case("content-length") {
+        return None;
+    }
+    value.trim().parse().ok()
+}
+
+/// Write a [`Frame`] in the little-endian opcode / little-endian length /
+/// payload wire format shared by [`FramedTransport`], [`IpcTransport`], and
+/// [`TcpTransport`] — they differ only in how the underlying stream is
+/// obtained, not in how bytes go over it once connected.
+async fn write_framed<W: AsyncWrite + Unpin>(stream: &mut W, frame: Frame) -> io::Result<()> {
+    stream.write_u32_le(frame.opcode).await?;
+    stream.write_u32_le(frame.payload.len() as u32).await?;
+    stream.write_all(&frame.payload).await?;
+    stream.flush().await
+}
+
+/// Read a [`Frame`] back out of the wire format [`write_framed`] produces.
+async fn read_framed<R: AsyncRead + Unpin>(stream: &mut R) -> io::Result<Frame> {
+    let opcode = stream.read_u32_le().await?;
+    let len = stream.read_u32_le().await? as usize;
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).await?;
+    Ok(Frame { opcode, payload })
+}
+
+#[async_trait::async_trait]
+impl Transport for FramedTransport {
+    async fn send(&self, frame: Frame) -> io::Result<()> {
+        write_framed(&mut *self.stream.lock().await, frame).await
+    }
+
+    async fn recv(&self) -> io::Result<Frame> {
+        read_framed(&mut *self.stream.lock().await).await
+    }
+}
+
+/// Same wire format as [`FramedTransport`], but the host dials out to an
+/// existing Unix domain socket / Windows named pipe that the core itself
+/// created and advertised via a `PRYX_CORE_IPC_PATH=` stdout line (mirroring
+/// how it advertises `PRYX_CORE_LISTEN_ADDR=` for its HTTP port), rather
+/// than the host binding ahead of time and waiting for the core to connect
+/// in. Modeled on ethers' IPC transport, which dials a known socket path
+/// instead of accepting a connection on one.
+pub struct IpcTransport {
+    #[cfg(unix)]
+    stream: AsyncMutex<tokio::net::UnixStream>,
+    #[cfg(windows)]
+    stream: AsyncMutex<tokio::net::windows::named_pipe::NamedPipeClient>,
+}
+
+impl std::fmt::Debug for IpcTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IpcTransport").finish_non_exhaustive()
+    }
+}
+
+impl IpcTransport {
+    #[cfg(unix)]
+    pub async fn connect(path: &std::path::Path) -> io::Result<Self> {
+        let stream = tokio::net::UnixStream::connect(path).await?;
+        Ok(Self {
+            stream: AsyncMutex::new(stream),
+        })
+    }
+
+    /// A named pipe server rejects a connection attempt with
+    /// `ERROR_PIPE_BUSY` while it's still finishing a previous client's
+    /// handshake; retry on a short sleep rather than surfacing that as a
+    /// hard failure, since it typically clears within milliseconds.
+    #[cfg(windows)]
+    pub async fn connect(path: &std::path::Path) -> io::Result<Self> {
+        use tokio::net::windows::named_pipe::ClientOptions;
+        const ERROR_PIPE_BUSY: i32 = 231;
+
+        let path_str = path.to_string_lossy();
+        loop {
+            match ClientOptions::new().open(path_str.as_ref()) {
+                Ok(client) => {
+                    return Ok(Self {
+                        stream: AsyncMutex::new(client),
+                    })
+                }
+                Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY) => {
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for IpcTransport {
+    async fn send(&self, frame: Frame) -> io::Result<()> {
+        write_framed(&mut *self.stream.lock().await, frame).await
+    }
+
+    async fn recv(&self) -> io::Result<Frame> {
+        read_framed(&mut *self.stream.lock().await).await
+    }
+}
+
+/// Same wire format as [`FramedTransport`] (little-endian opcode, then
+/// little-endian length, then the JSON body), but over a TCP socket to a
+/// core that's already running elsewhere rather than a local Unix domain
+/// socket / named pipe bound for a child this process spawned. Used by
+/// `SidecarSpawnMode::Attach`.
+pub struct TcpTransport {
+    stream: AsyncMutex<tokio::net::TcpStream>,
+}
+
+impl std::fmt::Debug for TcpTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TcpTransport").finish_non_exhaustive()
+    }
+}
+
+impl TcpTransport {
+    pub fn new(stream: tokio::net::TcpStream) -> Self {
+        Self {
+            stream: AsyncMutex::new(stream),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for TcpTransport {
+    async fn send(&self, frame: Frame) -> io::Result<()> {
+        write_framed(&mut *self.stream.lock().await, frame).await
+    }
+
+    async fn recv(&self) -> io::Result<Frame> {
+        read_framed(&mut *self.stream.lock().await).await
+    }
+}